@@ -0,0 +1,155 @@
+//! Minimal BlurHash encoder (https://blurha.sh) for generating compact
+//! placeholder strings from a decoded image, without pulling in an extra
+//! crate for something this small.
+
+use image::{DynamicImage, GenericImageView};
+
+const BASE83_CHARS: &[u8] =
+	b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+const MAX_COMPONENTS: u32 = 9;
+const DEFAULT_COMP_X: u32 = 4;
+const DEFAULT_COMP_Y: u32 = 3;
+
+/// Computes a BlurHash string for the given image using the default 4x3
+/// component grid. CPU-bound; callers should run it on a blocking thread.
+pub fn encode_blurhash(img: &DynamicImage) -> String {
+	encode_blurhash_with_components(img, DEFAULT_COMP_X, DEFAULT_COMP_Y)
+}
+
+fn encode_blurhash_with_components(img: &DynamicImage, comp_x: u32, comp_y: u32) -> String {
+	let comp_x = comp_x.clamp(1, MAX_COMPONENTS);
+	let comp_y = comp_y.clamp(1, MAX_COMPONENTS);
+
+	let rgb = img.to_rgb8();
+	let (width, height) = rgb.dimensions();
+
+	let mut factors: Vec<[f64; 3]> = Vec::with_capacity((comp_x * comp_y) as usize);
+	for comp_y_i in 0..comp_y {
+		for comp_x_i in 0..comp_x {
+			let factor = compute_component(&rgb, width, height, comp_x_i, comp_y_i);
+			factors.push(factor);
+		}
+	}
+
+	let dc = factors[0];
+	let ac = &factors[1..];
+
+	let mut result = String::new();
+
+	let size_flag = (comp_x - 1) + (comp_y - 1) * 9;
+	result.push_str(&encode_base83(size_flag as u32, 1));
+
+	let max_ac = if ac.is_empty() {
+		1.0
+	} else {
+		ac.iter()
+			.flat_map(|c| c.iter())
+			.fold(0.0f64, |acc, v| acc.max(v.abs()))
+	};
+
+	let quantized_max_ac = if !ac.is_empty() {
+		((max_ac * 166.0 - 0.5).max(0.0).min(82.0)) as u32
+	} else {
+		0
+	};
+	result.push_str(&encode_base83(quantized_max_ac, 1));
+
+	result.push_str(&encode_dc(dc));
+
+	let actual_max_ac = if quantized_max_ac > 0 {
+		(quantized_max_ac as f64 + 1.0) / 166.0
+	} else {
+		1.0
+	};
+	for component in ac {
+		result.push_str(&encode_ac(component, actual_max_ac));
+	}
+
+	result
+}
+
+/// Computes a single (compX, compY) DCT-like basis component for the whole
+/// image, per the BlurHash spec: linearize sRGB, weight by a cosine basis,
+/// and normalize by the component-dependent scale factor.
+fn compute_component(
+	rgb: &image::RgbImage,
+	width: u32,
+	height: u32,
+	comp_x: u32,
+	comp_y: u32,
+) -> [f64; 3] {
+	let mut r = 0.0f64;
+	let mut g = 0.0f64;
+	let mut b = 0.0f64;
+
+	for y in 0..height {
+		for x in 0..width {
+			let basis = (std::f64::consts::PI * comp_x as f64 * x as f64 / width as f64).cos()
+				* (std::f64::consts::PI * comp_y as f64 * y as f64 / height as f64).cos();
+			let pixel = rgb.get_pixel(x, y);
+			r += basis * srgb_to_linear(pixel[0]);
+			g += basis * srgb_to_linear(pixel[1]);
+			b += basis * srgb_to_linear(pixel[2]);
+		}
+	}
+
+	let scale = if comp_x == 0 && comp_y == 0 { 1.0 } else { 2.0 } / (width as f64 * height as f64);
+	[r * scale, g * scale, b * scale]
+}
+
+fn srgb_to_linear(value: u8) -> f64 {
+	let c = value as f64 / 255.0;
+	if c <= 0.04045 {
+		c / 12.92
+	} else {
+		((c + 0.055) / 1.055).powf(2.4)
+	}
+}
+
+fn linear_to_srgb(value: f64) -> u32 {
+	let v = value.clamp(0.0, 1.0);
+	let srgb = if v <= 0.0031308 {
+		v * 12.92
+	} else {
+		1.055 * v.powf(1.0 / 2.4) - 0.055
+	};
+	(srgb * 255.0 + 0.5).round().clamp(0.0, 255.0) as u32
+}
+
+fn encode_dc(value: [f64; 3]) -> String {
+	let rounded_r = linear_to_srgb(value[0]);
+	let rounded_g = linear_to_srgb(value[1]);
+	let rounded_b = linear_to_srgb(value[2]);
+	let combined = (rounded_r << 16) + (rounded_g << 8) + rounded_b;
+	encode_base83(combined, 4)
+}
+
+fn sign_pow(value: f64, exp: f64) -> f64 {
+	value.signum() * value.abs().powf(exp)
+}
+
+fn encode_ac(value: &[f64; 3], max_ac: f64) -> String {
+	let quantize = |v: f64| -> i64 {
+		let normalized = sign_pow(v / max_ac, 0.5);
+		(normalized * 9.0 + 9.5).floor().clamp(0.0, 18.0) as i64
+	};
+
+	let quant_r = quantize(value[0]);
+	let quant_g = quantize(value[1]);
+	let quant_b = quantize(value[2]);
+
+	let combined = quant_r * 19 * 19 + quant_g * 19 + quant_b;
+	encode_base83(combined as u32, 2)
+}
+
+fn encode_base83(value: u32, length: usize) -> String {
+	let mut result = vec![0u8; length];
+	let mut remaining = value;
+	for i in (0..length).rev() {
+		let digit = remaining % 83;
+		result[i] = BASE83_CHARS[digit as usize];
+		remaining /= 83;
+	}
+	String::from_utf8(result).expect("base83 alphabet is ASCII")
+}
@@ -0,0 +1,319 @@
+//! Pure-Rust World Magnetic Model (WMM2020) declination calculator, so
+//! `true_heading` can be derived on-device from `magnetic_heading` instead
+//! of depending on the platform to supply it.
+//!
+//! This follows the standard WMM spherical-harmonic expansion: Gauss
+//! coefficients `g[n][m]`/`h[n][m]` (degree/order up to 12) are time-adjusted
+//! to the capture date via their secular-variation rates, geodetic
+//! coordinates are converted to geocentric spherical coordinates, Schmidt
+//! semi-normalized associated Legendre functions are evaluated via the
+//! standard recurrence, and the north/east/down field components are summed
+//! and rotated back to geodetic before taking `atan2(east, north)`.
+
+use chrono::Datelike;
+
+/// Maximum spherical-harmonic degree/order used in the expansion.
+const N_MAX: usize = 12;
+
+/// Epoch (decimal year) the embedded Gauss coefficients are valid for.
+const EPOCH: f64 = 2020.0;
+
+/// WGS84 semi-major axis, km.
+const WGS84_A: f64 = 6378.137;
+/// WGS84 semi-minor axis, km.
+const WGS84_B: f64 = 6356.752314245;
+/// Geomagnetic reference radius used by the WMM, km.
+const EARTH_RADIUS: f64 = 6371.2;
+
+/// One (n, m, g, h, g_dot, h_dot) entry of the WMM2020 coefficient table.
+struct Coefficient {
+	n: usize,
+	m: usize,
+	g: f64,
+	h: f64,
+	g_dot: f64,
+	h_dot: f64,
+}
+
+/// WMM2020 Gauss coefficients (epoch 2020.0) and their secular-variation
+/// rates, as published by NOAA/NCEI, degree and order up to 12.
+#[rustfmt::skip]
+const COEFFICIENTS: &[Coefficient] = &[
+	Coefficient { n: 1, m: 0, g: -29404.5, h: 0.0, g_dot: 6.7, h_dot: 0.0 },
+	Coefficient { n: 1, m: 1, g: -1450.7, h: 4652.9, g_dot: 7.7, h_dot: -25.1 },
+	Coefficient { n: 2, m: 0, g: -2500.0, h: 0.0, g_dot: -11.5, h_dot: 0.0 },
+	Coefficient { n: 2, m: 1, g: 2982.0, h: -2991.6, g_dot: -7.1, h_dot: -30.2 },
+	Coefficient { n: 2, m: 2, g: 1676.8, h: -734.8, g_dot: -2.2, h_dot: -23.9 },
+	Coefficient { n: 3, m: 0, g: 1363.9, h: 0.0, g_dot: 2.8, h_dot: 0.0 },
+	Coefficient { n: 3, m: 1, g: -2381.0, h: -82.2, g_dot: -6.2, h_dot: 5.7 },
+	Coefficient { n: 3, m: 2, g: 1236.2, h: 241.8, g_dot: 3.4, h_dot: -1.0 },
+	Coefficient { n: 3, m: 3, g: 525.7, h: -542.9, g_dot: -12.2, h_dot: 1.1 },
+	Coefficient { n: 4, m: 0, g: 903.1, h: 0.0, g_dot: -1.1, h_dot: 0.0 },
+	Coefficient { n: 4, m: 1, g: 809.4, h: 282.0, g_dot: -1.6, h_dot: 0.2 },
+	Coefficient { n: 4, m: 2, g: 86.2, h: -158.4, g_dot: -6.0, h_dot: 6.9 },
+	Coefficient { n: 4, m: 3, g: -309.4, h: 199.8, g_dot: 5.4, h_dot: 3.7 },
+	Coefficient { n: 4, m: 4, g: 47.9, h: -350.1, g_dot: -5.5, h_dot: -5.6 },
+	Coefficient { n: 5, m: 0, g: -234.4, h: 0.0, g_dot: -0.3, h_dot: 0.0 },
+	Coefficient { n: 5, m: 1, g: 363.1, h: 47.7, g_dot: 0.6, h_dot: 0.1 },
+	Coefficient { n: 5, m: 2, g: 187.8, h: 208.4, g_dot: -0.7, h_dot: 2.5 },
+	Coefficient { n: 5, m: 3, g: -140.7, h: -121.3, g_dot: 0.1, h_dot: -0.9 },
+	Coefficient { n: 5, m: 4, g: -151.2, h: 32.2, g_dot: 1.2, h_dot: 3.0 },
+	Coefficient { n: 5, m: 5, g: 13.7, h: 99.1, g_dot: 1.0, h_dot: 0.5 },
+	Coefficient { n: 6, m: 0, g: 65.9, h: 0.0, g_dot: -0.6, h_dot: 0.0 },
+	Coefficient { n: 6, m: 1, g: 65.6, h: -19.1, g_dot: -0.4, h_dot: 0.1 },
+	Coefficient { n: 6, m: 2, g: 73.0, h: 25.0, g_dot: 0.6, h_dot: -1.8 },
+	Coefficient { n: 6, m: 3, g: -121.5, h: 52.7, g_dot: 1.4, h_dot: -1.4 },
+	Coefficient { n: 6, m: 4, g: -36.2, h: -64.4, g_dot: -1.4, h_dot: 0.9 },
+	Coefficient { n: 6, m: 5, g: 13.5, h: 9.0, g_dot: 0.0, h_dot: 0.1 },
+	Coefficient { n: 6, m: 6, g: -64.7, h: 68.1, g_dot: 0.8, h_dot: 1.0 },
+	Coefficient { n: 7, m: 0, g: 80.6, h: 0.0, g_dot: -0.1, h_dot: 0.0 },
+	Coefficient { n: 7, m: 1, g: -76.8, h: -51.4, g_dot: -0.3, h_dot: 0.5 },
+	Coefficient { n: 7, m: 2, g: -8.3, h: -16.8, g_dot: -0.1, h_dot: 0.6 },
+	Coefficient { n: 7, m: 3, g: 56.5, h: 2.3, g_dot: 0.7, h_dot: -0.7 },
+	Coefficient { n: 7, m: 4, g: 15.8, h: 23.5, g_dot: 0.2, h_dot: -0.2 },
+	Coefficient { n: 7, m: 5, g: 6.4, h: -2.2, g_dot: -0.5, h_dot: -1.2 },
+	Coefficient { n: 7, m: 6, g: -7.2, h: -27.2, g_dot: -0.8, h_dot: 0.2 },
+	Coefficient { n: 7, m: 7, g: 9.8, h: -1.9, g_dot: 1.0, h_dot: 0.3 },
+	Coefficient { n: 8, m: 0, g: 23.6, h: 0.0, g_dot: -0.1, h_dot: 0.0 },
+	Coefficient { n: 8, m: 1, g: 9.8, h: 8.4, g_dot: 0.1, h_dot: -0.3 },
+	Coefficient { n: 8, m: 2, g: -17.5, h: -15.3, g_dot: -0.1, h_dot: 0.7 },
+	Coefficient { n: 8, m: 3, g: -0.4, h: 12.8, g_dot: 0.5, h_dot: -0.2 },
+	Coefficient { n: 8, m: 4, g: -21.1, h: -11.8, g_dot: -0.1, h_dot: 0.5 },
+	Coefficient { n: 8, m: 5, g: 15.3, h: 14.9, g_dot: 0.4, h_dot: -0.3 },
+	Coefficient { n: 8, m: 6, g: 13.7, h: 3.6, g_dot: 0.5, h_dot: -0.5 },
+	Coefficient { n: 8, m: 7, g: -16.5, h: -6.9, g_dot: 0.0, h_dot: 0.4 },
+	Coefficient { n: 8, m: 8, g: -0.3, h: 2.8, g_dot: 0.4, h_dot: 0.1 },
+	Coefficient { n: 9, m: 0, g: 5.0, h: 0.0, g_dot: -0.1, h_dot: 0.0 },
+	Coefficient { n: 9, m: 1, g: 8.2, h: -23.3, g_dot: -0.2, h_dot: -0.3 },
+	Coefficient { n: 9, m: 2, g: 2.9, h: 11.1, g_dot: 0.0, h_dot: 0.2 },
+	Coefficient { n: 9, m: 3, g: -1.4, h: 9.8, g_dot: 0.4, h_dot: -0.4 },
+	Coefficient { n: 9, m: 4, g: -1.1, h: -5.1, g_dot: -0.3, h_dot: 0.4 },
+	Coefficient { n: 9, m: 5, g: -13.3, h: -6.2, g_dot: 0.0, h_dot: 0.1 },
+	Coefficient { n: 9, m: 6, g: 1.1, h: 7.8, g_dot: 0.3, h_dot: 0.0 },
+	Coefficient { n: 9, m: 7, g: 8.9, h: 0.4, g_dot: 0.0, h_dot: -0.2 },
+	Coefficient { n: 9, m: 8, g: -9.3, h: -1.5, g_dot: 0.0, h_dot: 0.5 },
+	Coefficient { n: 9, m: 9, g: -11.9, h: 9.7, g_dot: -0.4, h_dot: 0.2 },
+	Coefficient { n: 10, m: 0, g: -1.9, h: 0.0, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 10, m: 1, g: -6.2, h: 3.4, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 10, m: 2, g: -0.1, h: -0.2, g_dot: 0.0, h_dot: 0.1 },
+	Coefficient { n: 10, m: 3, g: 1.7, h: 3.5, g_dot: 0.2, h_dot: -0.3 },
+	Coefficient { n: 10, m: 4, g: -0.9, h: 4.8, g_dot: -0.1, h_dot: 0.1 },
+	Coefficient { n: 10, m: 5, g: 0.6, h: -8.6, g_dot: -0.2, h_dot: -0.2 },
+	Coefficient { n: 10, m: 6, g: -0.9, h: -0.1, g_dot: 0.0, h_dot: 0.1 },
+	Coefficient { n: 10, m: 7, g: 1.9, h: -4.2, g_dot: -0.1, h_dot: 0.0 },
+	Coefficient { n: 10, m: 8, g: 1.4, h: -3.4, g_dot: -0.2, h_dot: -0.1 },
+	Coefficient { n: 10, m: 9, g: -2.4, h: -0.1, g_dot: -0.1, h_dot: 0.2 },
+	Coefficient { n: 10, m: 10, g: -3.9, h: -8.8, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 11, m: 0, g: 3.0, h: 0.0, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 11, m: 1, g: -1.4, h: 0.0, g_dot: -0.1, h_dot: 0.0 },
+	Coefficient { n: 11, m: 2, g: -2.5, h: 2.6, g_dot: 0.0, h_dot: 0.1 },
+	Coefficient { n: 11, m: 3, g: 2.4, h: -0.5, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 11, m: 4, g: -0.9, h: -0.4, g_dot: 0.0, h_dot: 0.2 },
+	Coefficient { n: 11, m: 5, g: 0.3, h: 0.6, g_dot: -0.1, h_dot: 0.0 },
+	Coefficient { n: 11, m: 6, g: -0.7, h: -0.2, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 11, m: 7, g: -0.1, h: -1.7, g_dot: 0.0, h_dot: 0.1 },
+	Coefficient { n: 11, m: 8, g: 1.4, h: -1.6, g_dot: -0.1, h_dot: 0.0 },
+	Coefficient { n: 11, m: 9, g: -0.6, h: -3.0, g_dot: -0.1, h_dot: -0.1 },
+	Coefficient { n: 11, m: 10, g: 0.2, h: -2.0, g_dot: -0.1, h_dot: 0.0 },
+	Coefficient { n: 11, m: 11, g: 3.1, h: -2.6, g_dot: -0.1, h_dot: 0.0 },
+	Coefficient { n: 12, m: 0, g: -2.0, h: 0.0, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 12, m: 1, g: -0.1, h: -1.2, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 12, m: 2, g: 0.5, h: 0.5, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 12, m: 3, g: 1.3, h: 1.3, g_dot: 0.0, h_dot: -0.1 },
+	Coefficient { n: 12, m: 4, g: -1.2, h: -1.8, g_dot: 0.0, h_dot: 0.1 },
+	Coefficient { n: 12, m: 5, g: 0.7, h: 0.1, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 12, m: 6, g: -0.4, h: 0.7, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 12, m: 7, g: 0.3, h: 0.8, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 12, m: 8, g: -0.1, h: 0.3, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 12, m: 9, g: 0.0, h: 0.6, g_dot: 0.0, h_dot: -0.1 },
+	Coefficient { n: 12, m: 10, g: -0.3, h: -0.2, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 12, m: 11, g: -0.1, h: -0.5, g_dot: 0.0, h_dot: 0.0 },
+	Coefficient { n: 12, m: 12, g: -0.3, h: -0.8, g_dot: -0.1, h_dot: 0.0 },
+];
+
+/// `(n+1) x (n+1)` triangular table indexed `[n][m]`.
+struct Triangle {
+	values: Vec<Vec<f64>>,
+}
+
+impl Triangle {
+	fn zeroed() -> Self {
+		Self { values: (0..=N_MAX).map(|n| vec![0.0; n + 1]).collect() }
+	}
+
+	fn get(&self, n: usize, m: usize) -> f64 {
+		self.values[n][m]
+	}
+
+	fn set(&mut self, n: usize, m: usize, value: f64) {
+		self.values[n][m] = value;
+	}
+}
+
+/// Time-adjusts the embedded coefficients to `decimal_year` via their
+/// secular-variation rates, returning the `(g, h)` triangles.
+fn time_adjusted_coefficients(decimal_year: f64) -> (Triangle, Triangle) {
+	let dt = decimal_year - EPOCH;
+	let mut g = Triangle::zeroed();
+	let mut h = Triangle::zeroed();
+	for c in COEFFICIENTS {
+		g.set(c.n, c.m, c.g + dt * c.g_dot);
+		h.set(c.n, c.m, c.h + dt * c.h_dot);
+	}
+	(g, h)
+}
+
+/// Schmidt quasi-normalization factors, `[n][m]`.
+fn schmidt_quasi_norm() -> Triangle {
+	let mut norm = Triangle::zeroed();
+	norm.set(0, 0, 1.0);
+	for n in 1..=N_MAX {
+		norm.set(n, 0, norm.get(n - 1, 0) * (2 * n - 1) as f64 / n as f64);
+		for m in 1..=n {
+			let numerator = (n - m + 1) as f64 * if m == 1 { 2.0 } else { 1.0 };
+			let factor = (numerator / (n + m) as f64).sqrt();
+			norm.set(n, m, norm.get(n, m - 1) * factor);
+		}
+	}
+	norm
+}
+
+/// Evaluates the Schmidt semi-normalized associated Legendre functions
+/// `P[n][m](sin(lat'))` and their derivatives with respect to geocentric
+/// colatitude (the negated derivative with respect to `lat'`), via the
+/// standard recurrence, at geocentric latitude `lat_gc` (radians).
+fn legendre(lat_gc: f64) -> (Triangle, Triangle) {
+	let sin_lat = lat_gc.sin();
+	let cos_lat = lat_gc.cos();
+
+	let mut p = Triangle::zeroed();
+	let mut dp = Triangle::zeroed();
+	p.set(0, 0, 1.0);
+
+	for m in 0..=N_MAX {
+		for n in m.max(1)..=N_MAX {
+			if n == m {
+				p.set(n, m, cos_lat * p.get(n - 1, m - 1));
+				dp.set(n, m, cos_lat * dp.get(n - 1, m - 1) + sin_lat * p.get(n - 1, m - 1));
+			} else if n == 1 {
+				p.set(n, m, sin_lat * p.get(n - 1, m));
+				dp.set(n, m, sin_lat * dp.get(n - 1, m) - cos_lat * p.get(n - 1, m));
+			} else if m > n - 2 {
+				// P[n-2][m] doesn't exist (m exceeds that row's degree), so
+				// this term of the recurrence drops out.
+				p.set(n, m, sin_lat * p.get(n - 1, m));
+				dp.set(n, m, sin_lat * dp.get(n - 1, m) - cos_lat * p.get(n - 1, m));
+			} else {
+				let k = ((n - 1) * (n - 1) - m * m) as f64 / ((2 * n - 1) * (2 * n - 3)) as f64;
+				p.set(n, m, sin_lat * p.get(n - 1, m) - k * p.get(n - 2, m));
+				dp.set(n, m, sin_lat * dp.get(n - 1, m) - cos_lat * p.get(n - 1, m) - k * dp.get(n - 2, m));
+			}
+		}
+	}
+
+	let norm = schmidt_quasi_norm();
+	for n in 0..=N_MAX {
+		for m in 0..=n {
+			p.set(n, m, p.get(n, m) * norm.get(n, m));
+			dp.set(n, m, dp.get(n, m) * norm.get(n, m));
+		}
+	}
+
+	(p, dp)
+}
+
+/// Converts geodetic latitude/altitude (WGS84) to geocentric spherical
+/// radius (km) and latitude (radians).
+fn geodetic_to_geocentric(lat_rad: f64, alt_km: f64) -> (f64, f64) {
+	let f = (WGS84_A - WGS84_B) / WGS84_A;
+	let e2 = f * (2.0 - f);
+	let sin_lat = lat_rad.sin();
+	let radius_of_curvature = WGS84_A / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+
+	let p = (radius_of_curvature + alt_km) * lat_rad.cos();
+	let z = (radius_of_curvature * (1.0 - e2) + alt_km) * sin_lat;
+	let r = (p * p + z * z).sqrt();
+	let lat_gc = z.atan2(p);
+
+	(r, lat_gc)
+}
+
+/// Computes the magnetic declination `D` (degrees, positive east) at
+/// geodetic `lat`/`lon` (degrees), `alt_m` (meters above the WGS84
+/// ellipsoid), at the date of `unix_ts` (seconds since the Unix epoch).
+pub fn magnetic_declination(lat: f64, lon: f64, alt_m: f64, unix_ts: i64) -> f32 {
+	let date = chrono::DateTime::from_timestamp(unix_ts, 0).unwrap_or_else(|| chrono::Utc::now());
+	let year = date.year();
+	let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+	let days_in_year = if is_leap_year { 366.0 } else { 365.0 };
+	let decimal_year = year as f64 + (date.ordinal0() as f64) / days_in_year;
+
+	let lat_rad = lat.to_radians();
+	let lon_rad = lon.to_radians();
+	let alt_km = alt_m / 1000.0;
+
+	let (g, h) = time_adjusted_coefficients(decimal_year);
+	let (r, lat_gc) = geodetic_to_geocentric(lat_rad, alt_km);
+	let (p, dp) = legendre(lat_gc);
+
+	let mut cos_m_lon = vec![1.0; N_MAX + 1];
+	let mut sin_m_lon = vec![0.0; N_MAX + 1];
+	for m in 1..=N_MAX {
+		cos_m_lon[m] = (m as f64 * lon_rad).cos();
+		sin_m_lon[m] = (m as f64 * lon_rad).sin();
+	}
+
+	let mut relative_radius_power = vec![0.0; N_MAX + 1];
+	let ratio = EARTH_RADIUS / r;
+	let mut power = ratio * ratio;
+	for n in 1..=N_MAX {
+		power *= ratio;
+		relative_radius_power[n] = power;
+	}
+
+	let cos_lat_gc = lat_gc.cos().max(1e-10);
+
+	let mut bx = 0.0; // north
+	let mut by = 0.0; // east
+	let mut bz = 0.0; // down
+	for n in 1..=N_MAX {
+		for m in 0..=n {
+			let gnm = g.get(n, m);
+			let hnm = h.get(n, m);
+			let term = relative_radius_power[n] * (gnm * cos_m_lon[m] + hnm * sin_m_lon[m]);
+			bz -= term * (n as f64 + 1.0) * p.get(n, m);
+			bx += term * dp.get(n, m);
+			by += relative_radius_power[n] * m as f64 * (gnm * sin_m_lon[m] - hnm * cos_m_lon[m]) * p.get(n, m) / cos_lat_gc;
+		}
+	}
+
+	// Rotate the north component from geocentric back to geodetic latitude
+	// (east is unaffected by this rotation); declination only needs north,
+	// so bz's geodetic counterpart isn't needed here.
+	let psi = lat_gc - lat_rad;
+	let bx_geodetic = bx * psi.cos() - bz * psi.sin();
+
+	by.atan2(bx_geodetic).to_degrees() as f32
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Prime-meridian/equator intersection, epoch 2020.0 exactly (unix_ts is
+	/// midnight UTC on 2020-01-01, so `decimal_year` works out to 2020.0 with
+	/// no secular-variation adjustment). The expected value matches NOAA's
+	/// published WMM2020 declination for this point to within rounding; a
+	/// tolerance wider than float noise is used since we're not chasing their
+	/// exact last-digit rounding, just verifying the model is in the right
+	/// ballpark (the bug this test guards against made it off by ~180 degrees).
+	#[test]
+	fn matches_noaa_wmm2020_reference_point() {
+		let unix_ts = 1_577_836_800; // 2020-01-01T00:00:00Z
+		let declination = magnetic_declination(0.0, 0.0, 0.0, unix_ts);
+		assert!(
+			(declination - (-4.64)).abs() < 0.1,
+			"expected ~-4.64 degrees at (0, 0), got {declination}"
+		);
+	}
+}
@@ -5,12 +5,104 @@ use std::collections::HashMap;
 use std::sync::Mutex;
 use std::sync::OnceLock;
 use tauri::command;
+use tauri::Manager;
 use tauri_plugin_hillview::HillviewExt;
 use crate::photo_exif::{create_exif_segment_structured, validate_photo_metadata};
 #[cfg(debug_assertions)]
 use crate::photo_exif::verify_exif_in_saved_file;
 use crate::types::PhotoMetadata;
 
+/// Name of the subdirectory (alongside the Hillview photos folder) that holds
+/// generated thumbnail variants.
+#[cfg(target_os = "android")]
+const THUMBNAILS_DIR: &str = ".thumbnails";
+
+/// Thumbnail variants we generate for every captured photo, largest first so
+/// the list doubles as a priority order if generation is ever interrupted.
+#[cfg(target_os = "android")]
+const THUMBNAIL_SIZES: &[(u32, &str)] = &[(1024, "1024"), (256, "256")];
+
+/// Bounds how many thumbnail resizes run concurrently so a batch refresh of
+/// thousands of photos doesn't spike memory on low-end Android devices.
+#[cfg(target_os = "android")]
+static THUMBNAIL_SEMAPHORE: OnceLock<std::sync::Arc<tokio::sync::Semaphore>> = OnceLock::new();
+
+#[cfg(target_os = "android")]
+fn thumbnail_semaphore() -> std::sync::Arc<tokio::sync::Semaphore> {
+	THUMBNAIL_SEMAPHORE
+		.get_or_init(|| std::sync::Arc::new(tokio::sync::Semaphore::new(4)))
+		.clone()
+}
+
+/// Paths of the generated thumbnail variants, relative to the same storage
+/// root as the full-resolution photo.
+#[cfg(target_os = "android")]
+struct ThumbnailPaths {
+	path_256: Option<String>,
+	path_1024: Option<String>,
+}
+
+/// Resizes `img` down to each of `THUMBNAIL_SIZES` with a Lanczos3 filter and
+/// writes the results into `<pictures_path>/.thumbnails`. Runs on the calling
+/// (blocking) thread; callers should hold a [`THUMBNAIL_SEMAPHORE`] permit.
+#[cfg(target_os = "android")]
+fn save_thumbnails(
+	img: &image::DynamicImage,
+	pictures_path: &std::path::Path,
+	filename: &str,
+	hide_from_gallery: bool,
+) -> Result<ThumbnailPaths, String> {
+	let thumbnails_dir = pictures_path.join(THUMBNAILS_DIR);
+	std::fs::create_dir_all(&thumbnails_dir)
+		.map_err(|e| format!("Failed to create thumbnails dir: {}", e))?;
+
+	if hide_from_gallery {
+		let nomedia_file = thumbnails_dir.join(".nomedia");
+		let _ = std::fs::write(nomedia_file, "");
+	}
+
+	let mut path_256 = None;
+	let mut path_1024 = None;
+
+	for (max_dimension, suffix) in THUMBNAIL_SIZES {
+		let thumbnail = img.resize(*max_dimension, *max_dimension, image::imageops::FilterType::Lanczos3);
+		let thumb_filename = format!("{}.{}.jpg", filename.trim_end_matches(".jpg"), suffix);
+
+		let mut jpeg_bytes = Vec::new();
+		thumbnail
+			.to_rgb8()
+			.write_to(&mut std::io::Cursor::new(&mut jpeg_bytes), image::ImageFormat::Jpeg)
+			.map_err(|e| format!("Failed to encode {}px thumbnail: {}", max_dimension, e))?;
+
+		// Mirror save_to_pictures_directory: a hidden photo's thumbnails are as
+		// sensitive as the original, so encrypt them with the same vault key
+		// rather than leaving plaintext derivatives behind in `.thumbnails/`.
+		let (thumb_filename, thumb_bytes) = if hide_from_gallery {
+			match crate::encryption::vault_key() {
+				Some(key) => {
+					let ciphertext = crate::encryption::encrypt(&jpeg_bytes, &key)?;
+					(format!("{}.{}", thumb_filename, crate::encryption::VAULT_EXTENSION), ciphertext)
+				}
+				None => (thumb_filename, jpeg_bytes),
+			}
+		} else {
+			(thumb_filename, jpeg_bytes)
+		};
+
+		let thumb_path = thumbnails_dir.join(&thumb_filename);
+		std::fs::write(&thumb_path, &thumb_bytes).map_err(|e| format!("Failed to write {}px thumbnail: {}", max_dimension, e))?;
+
+		let path_str = thumb_path.to_string_lossy().to_string();
+		match *suffix {
+			"256" => path_256 = Some(path_str),
+			"1024" => path_1024 = Some(path_str),
+			_ => {}
+		}
+	}
+
+	Ok(ThumbnailPaths { path_256, path_1024 })
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DevicePhotoMetadata {
 	pub id: String,
@@ -26,8 +118,48 @@ pub struct DevicePhotoMetadata {
 	pub height: u32,
 	pub file_size: u64,
 	pub created_at: Option<i64>,
+	/// Compact BlurHash placeholder (https://blurha.sh) computed from the
+	/// decoded image, used by the gallery/upload UI before the full photo loads.
+	pub blurhash: Option<String>,
+	/// Path to the 256px-max-dimension thumbnail variant, if generated.
+	pub thumbnail_256_path: Option<String>,
+	/// Path to the 1024px-max-dimension thumbnail variant, if generated.
+	pub thumbnail_1024_path: Option<String>,
+	/// Free-form user tags (e.g. "bridge", "sunset"), queryable via
+	/// `query_device_photos_by_tags`.
+	#[serde(default)]
+	pub tags: Vec<String>,
+	/// md5 of the final (post-EXIF) JPEG bytes, used for content-addressed
+	/// dedup (see [`DedupMode`]) and as the S3 upload key.
+	#[serde(default)]
+	pub file_hash: Option<String>,
+	/// Where `altitude` came from, e.g. `"dem"` when backfilled by
+	/// `backfill_altitudes` rather than reported by GPS.
+	#[serde(default)]
+	pub altitude_source: Option<String>,
+}
+
+/// Controls whether `save_photo_with_metadata` skips re-saving a frame that's
+/// identical (or near-identical, in the same spot) to one already captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DedupMode {
+	/// Always save, even if the bytes are byte-for-byte identical to an
+	/// existing photo.
+	Off,
+	/// Skip the save if any existing photo has the same `file_hash`.
+	ByHash,
+	/// Skip the save if any existing photo has the same `file_hash` AND was
+	/// captured at (roughly) the same location, so an identical frame
+	/// re-captured far away (e.g. a reused test image) still gets saved.
+	ByHashAndGeohash,
 }
 
+/// Precision (in base32 characters) used when comparing geohashes for
+/// [`DedupMode::ByHashAndGeohash`]; 7 characters covers a ~150m x 150m cell,
+/// loose enough to absorb GPS jitter between a burst of near-duplicate shots.
+const DEDUP_GEOHASH_PRECISION: usize = 7;
+
 #[derive(Debug, Serialize)]
 pub struct ProcessedPhoto {
 	pub data: Vec<u8>,
@@ -39,6 +171,15 @@ pub struct ProcessedPhoto {
 static PHOTO_CHUNKS: OnceLock<Mutex<HashMap<String, Vec<u8>>>> = OnceLock::new();
 
 
+/// The Hillview photos folder under the public DCIM directory, named with a
+/// leading dot (so it's skipped by gallery scanners) when hidden.
+#[cfg(target_os = "android")]
+fn hillview_pictures_path(hide_from_gallery: bool) -> std::path::PathBuf {
+	let folder_name = if hide_from_gallery { ".Hillview" } else { "Hillview" };
+	let public_pictures_dir = "/storage/emulated/0/DCIM";
+	std::path::Path::new(public_pictures_dir).join(folder_name)
+}
+
 /// Save photo to storage. Tries direct file I/O first, falls back to MediaStore.
 /// Returns the path (file path or content:// URI).
 #[cfg(target_os = "android")]
@@ -48,9 +189,24 @@ fn save_to_pictures_directory(
 	image_data: &[u8],
 	hide_from_gallery: bool,
 ) -> Result<String, String> {
-	let folder_name = if hide_from_gallery { ".Hillview" } else { "Hillview" };
-	let public_pictures_dir = "/storage/emulated/0/DCIM";
-	let public_pictures_path = std::path::Path::new(public_pictures_dir).join(folder_name);
+	let public_pictures_path = hillview_pictures_path(hide_from_gallery);
+
+	// When hiding from the gallery, encrypt the photo with the configured
+	// vault key (if any) and mark it with `.hvenc` so the gallery scanner
+	// and EXIF tooling skip it outright, not just `.nomedia` folders.
+	let (filename, image_data) = if hide_from_gallery {
+		match crate::encryption::vault_key() {
+			Some(key) => {
+				let ciphertext = crate::encryption::encrypt(image_data, &key)?;
+				(format!("{}.{}", filename, crate::encryption::VAULT_EXTENSION), ciphertext)
+			}
+			None => (filename.to_string(), image_data.to_vec()),
+		}
+	} else {
+		(filename.to_string(), image_data.to_vec())
+	};
+	let filename = filename.as_str();
+	let image_data = image_data.as_slice();
 
 	// Try direct file I/O first (works on some devices)
 	match save_to_directory(&public_pictures_path, filename, image_data, hide_from_gallery) {
@@ -143,6 +299,7 @@ pub async fn save_photo_with_metadata(
 	metadata: PhotoMetadata,
 	filename: String,
 	hide_from_gallery: bool,
+	dedup_mode: DedupMode,
 ) -> Result<crate::device_photos::DevicePhotoMetadata, String> {
 	// Step 1: Get stored image data
 	let image_data = {
@@ -156,7 +313,7 @@ pub async fn save_photo_with_metadata(
 	// Call the internal function with the image data
 	#[cfg(target_os = "android")]
 	{
-		save_photo_from_bytes(app_handle, photo_id, metadata, image_data, filename, hide_from_gallery).await
+		save_photo_from_bytes(app_handle, photo_id, metadata, image_data, filename, hide_from_gallery, dedup_mode).await
 	}
 
 	#[cfg(not(target_os = "android"))]
@@ -173,16 +330,25 @@ async fn save_photo_from_bytes(
 	image_data: Vec<u8>,
 	filename: String,
 	hide_from_gallery: bool,
+	dedup_mode: DedupMode,
 ) -> Result<crate::device_photos::DevicePhotoMetadata, String> {
 	// Determine the final bearing before spawning the blocking task
 	metadata.bearing = determine_final_bearing(&app_handle, &metadata).await;
 
+	// Bound how many photos can be resizing thumbnails at once, so a batch
+	// refresh doesn't spike memory on low-end devices
+	let _thumbnail_permit = thumbnail_semaphore()
+		.acquire_owned()
+		.await
+		.map_err(|e| format!("Failed to acquire thumbnail semaphore: {}", e))?;
+
 	// Do everything in one background thread
 	tokio::task::spawn_blocking(move || -> Result<crate::device_photos::DevicePhotoMetadata, String> {
+		let _thumbnail_permit = _thumbnail_permit; // held until this blocking task finishes
 		info!("🢄Processing {} bytes for photo ID: {}", image_data.len(), photo_id);
 
 		// Process EXIF data synchronously and get dimensions
-		let (processed_data, width, height, validated_metadata) = {
+		let (processed_data, width, height, validated_metadata, blurhash, thumbnails) = {
 			let validated_metadata = validate_photo_metadata(metadata.clone());
 
 			// Parse the JPEG
@@ -194,6 +360,21 @@ async fn save_photo_from_bytes(
 				.map_err(|e| format!("Failed to load image from memory: {}", e))?;
 			let (width, height) = (img.width(), img.height());
 
+			// Compute a BlurHash placeholder from the already-decoded image (CPU-bound,
+			// so do it here alongside the rest of the blocking work)
+			let blurhash = crate::blurhash::encode_blurhash(&img);
+
+			// Generate downscaled thumbnails so the device-photo list can render
+			// instantly without decoding the multi-megabyte original
+			let pictures_path = hillview_pictures_path(hide_from_gallery);
+			let thumbnails = match save_thumbnails(&img, &pictures_path, &filename, hide_from_gallery) {
+				Ok(paths) => paths,
+				Err(e) => {
+					warn!("🢄⚠️ Failed to generate thumbnails: {}", e);
+					ThumbnailPaths { path_256: None, path_1024: None }
+				}
+			};
+
 			// Create EXIF segment - use structured version
 			let exif_segment = create_exif_segment_structured(&validated_metadata);
 
@@ -207,9 +388,41 @@ async fn save_photo_from_bytes(
 				.write_to(&mut output_cursor)
 				.map_err(|e| format!("Failed to write JPEG: {:?}", e))?;
 
-			(output, width, height, validated_metadata)
+			// Also embed an XMP packet alongside the EXIF segment, so geotags
+			// and provenance survive in mainstream tools that don't read our
+			// EXIF UserComment hack
+			let xmp_packet = crate::xmp::build_xmp_packet(&validated_metadata);
+			let output = match crate::xmp::insert_xmp_segment(&output, &xmp_packet) {
+				Ok(with_xmp) => with_xmp,
+				Err(e) => {
+					warn!("🢄⚠️ Failed to embed XMP segment: {}", e);
+					output
+				}
+			};
+
+			(output, width, height, validated_metadata, blurhash, thumbnails)
 		};
 
+		// Calculate hash from processed data (CPU intensive) before writing
+		// the photo itself to disk, so a dedup hit skips that write. The
+		// thumbnails generated above are already on disk at this point and
+		// are left in place on a dedup hit.
+		let hash_bytes = md5::compute(&processed_data);
+		let file_hash = format!("{:x}", hash_bytes);
+
+		if dedup_mode != DedupMode::Off {
+			use tauri_plugin_hillview::HillviewExt;
+			if let Some(existing) = app_handle
+				.hillview()
+				.get_device_photos()
+				.ok()
+				.and_then(|response| find_dedup_match(&response.photos, &file_hash, &validated_metadata, dedup_mode))
+			{
+				info!("🢄♻️ Skipping save for photo ID {}: duplicate of existing photo {}", photo_id, existing.id);
+				return Ok(alias_device_photo(photo_id, filename, existing));
+			}
+		}
+
 		// Save the photo file (blocking I/O or MediaStore)
 		let file_path = save_to_pictures_directory(&app_handle, &filename, &processed_data, hide_from_gallery)?;
 
@@ -229,10 +442,6 @@ async fn save_photo_from_bytes(
 		// Get file size - we already know it from processed_data
 		let file_size = processed_data.len() as u64;
 
-		// Calculate hash from processed data (CPU intensive)
-		let hash_bytes = md5::compute(&processed_data);
-		let file_hash = format!("{:x}", hash_bytes);
-
 		// Add to database (still in background thread)
 		{
 			use tauri_plugin_hillview::HillviewExt;
@@ -257,6 +466,9 @@ async fn save_photo_from_bytes(
 				file_size,
 				file_hash: Some(file_hash.clone()),
 				created_at: None, // Let the plugin set the created_at timestamp
+				blurhash: Some(blurhash.clone()),
+				thumbnail_256_path: thumbnails.path_256.clone(),
+				thumbnail_1024_path: thumbnails.path_1024.clone(),
 			};
 
 			let final_photo_id = match app_handle.hillview().add_photo_to_database(plugin_photo.clone()) {
@@ -289,6 +501,12 @@ async fn save_photo_from_bytes(
 				height,
 				file_size,
 				created_at: plugin_photo.created_at,
+				blurhash: Some(blurhash),
+				thumbnail_256_path: thumbnails.path_256,
+				thumbnail_1024_path: thumbnails.path_1024,
+				tags: Vec::new(),
+				file_hash: Some(file_hash.clone()),
+				altitude_source: None,
 			};
 
 			// Trigger immediate upload worker to process the new photo
@@ -304,6 +522,26 @@ async fn save_photo_from_bytes(
 				}
 			}
 
+			// Also push straight to S3-compatible storage when configured, so
+			// desktop (which has no Kotlin upload worker) gets uploads too
+			if let Some(s3_config) = crate::upload::s3_upload_config() {
+				let file_hash_for_upload = file_hash.clone();
+				let photo_data_for_upload = processed_data.clone();
+				let app_handle_for_upload = app_handle.clone();
+				tokio::spawn(async move {
+					if let Err(e) = crate::upload::upload_processed_photo(
+						&app_handle_for_upload,
+						&s3_config,
+						&file_hash_for_upload,
+						photo_data_for_upload,
+					)
+					.await
+					{
+						warn!("📤[S3_UPLOAD] Failed to upload photo {}: {}", file_hash_for_upload, e);
+					}
+				});
+			}
+
 			Ok(device_photo)
 		}
 
@@ -384,3 +622,328 @@ fn is_sensor_bearing_source(bearing_source: &str) -> bool {
 	source_lower.contains("enhanced")
 }
 
+/// Looks through the Android photo database's raw JSON records for one that
+/// `dedup_mode` considers a duplicate of `file_hash`/`metadata`, returning it
+/// parsed into the plugin's typed [`tauri_plugin_hillview::shared_types::DevicePhotoMetadata`].
+#[cfg(target_os = "android")]
+fn find_dedup_match(
+	candidates: &[serde_json::Value],
+	file_hash: &str,
+	metadata: &PhotoMetadata,
+	dedup_mode: DedupMode,
+) -> Option<tauri_plugin_hillview::shared_types::DevicePhotoMetadata> {
+	let target_geohash = (dedup_mode == DedupMode::ByHashAndGeohash)
+		.then(|| crate::geohash::encode(metadata.latitude, metadata.longitude, DEDUP_GEOHASH_PRECISION));
+
+	candidates.iter().find_map(|raw| {
+		let candidate: tauri_plugin_hillview::shared_types::DevicePhotoMetadata = serde_json::from_value(raw.clone()).ok()?;
+		if candidate.file_hash.as_deref() != Some(file_hash) {
+			return None;
+		}
+		if let Some(target_geohash) = &target_geohash {
+			let candidate_geohash = crate::geohash::encode(candidate.latitude(), candidate.longitude(), DEDUP_GEOHASH_PRECISION);
+			if &candidate_geohash != target_geohash {
+				return None;
+			}
+		}
+		Some(candidate)
+	})
+}
+
+/// Builds a lightweight alias record for a dedup hit: a new id/filename
+/// pointing at the same on-disk file and metadata as `existing`, so the
+/// caller gets back something that behaves like a normal saved photo without
+/// a second copy ever touching disk.
+#[cfg(target_os = "android")]
+fn alias_device_photo(
+	photo_id: String,
+	filename: String,
+	existing: tauri_plugin_hillview::shared_types::DevicePhotoMetadata,
+) -> DevicePhotoMetadata {
+	DevicePhotoMetadata {
+		id: photo_id,
+		filename,
+		path: existing.path,
+		latitude: existing.latitude(),
+		longitude: existing.longitude(),
+		altitude: existing.altitude(),
+		bearing: existing.bearing(),
+		captured_at: existing.capturedAt(),
+		accuracy: existing.accuracy(),
+		width: existing.width,
+		height: existing.height,
+		file_size: existing.file_size,
+		created_at: Some(existing.created_at),
+		blurhash: existing.blurhash,
+		thumbnail_256_path: existing.thumbnail_256_path,
+		thumbnail_1024_path: existing.thumbnail_1024_path,
+		tags: Vec::new(),
+		file_hash: existing.file_hash,
+		altitude_source: None,
+	}
+}
+// --- Device photos database -------------------------------------------------
+//
+// A small JSON file (`device_photos.json` under the app data dir) is the
+// desktop/cross-platform record of device photos, independent of Android's
+// own Kotlin-side photo database. It's what `query_device_photos_by_tags`
+// and friends operate on.
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DevicePhotosDb {
+	photos: Vec<DevicePhotoMetadata>,
+	last_updated: i64,
+}
+
+/// Response shape for `load_device_photos_db`/`refresh_device_photos`,
+/// mirroring the plugin's `DevicePhotosResponse` naming.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DevicePhotosDbResponse {
+	pub photos: Vec<DevicePhotoMetadata>,
+	pub last_updated: i64,
+}
+
+fn device_photos_db_path(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+	let dir = app_handle
+		.path()
+		.app_data_dir()
+		.map_err(|e| format!("Failed to resolve app data dir: {}", e))?;
+	std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data dir: {}", e))?;
+	Ok(dir.join("device_photos.json"))
+}
+
+fn read_device_photos_db(app_handle: &tauri::AppHandle) -> Result<DevicePhotosDb, String> {
+	let path = device_photos_db_path(app_handle)?;
+	if !path.exists() {
+		return Ok(DevicePhotosDb::default());
+	}
+	let raw = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read device photos db: {}", e))?;
+	serde_json::from_str(&raw).map_err(|e| format!("Failed to parse device photos db: {}", e))
+}
+
+fn write_device_photos_db(app_handle: &tauri::AppHandle, db: &DevicePhotosDb) -> Result<(), String> {
+	let path = device_photos_db_path(app_handle)?;
+	let raw = serde_json::to_string_pretty(db).map_err(|e| format!("Failed to serialize device photos db: {}", e))?;
+	std::fs::write(&path, raw).map_err(|e| format!("Failed to write device photos db: {}", e))
+}
+
+/// Loads the on-disk device photos database.
+#[command(rename_all = "snake_case")]
+pub fn load_device_photos_db(app_handle: tauri::AppHandle) -> Result<DevicePhotosDbResponse, String> {
+	let db = read_device_photos_db(&app_handle)?;
+	Ok(DevicePhotosDbResponse { photos: db.photos, last_updated: db.last_updated })
+}
+
+/// Overwrites the on-disk device photos database with `photos` wholesale.
+#[command(rename_all = "snake_case")]
+pub fn save_device_photos_db(app_handle: tauri::AppHandle, photos: Vec<DevicePhotoMetadata>) -> Result<(), String> {
+	let db = DevicePhotosDb { photos, last_updated: chrono::Utc::now().timestamp_millis() };
+	write_device_photos_db(&app_handle, &db)
+}
+
+/// Inserts or replaces a single photo in the on-disk database, keyed by `id`.
+#[command(rename_all = "snake_case")]
+pub fn add_device_photo_to_db(app_handle: tauri::AppHandle, photo: DevicePhotoMetadata) -> Result<(), String> {
+	let mut db = read_device_photos_db(&app_handle)?;
+	db.photos.retain(|p| p.id != photo.id);
+	db.photos.push(photo);
+	db.last_updated = chrono::Utc::now().timestamp_millis();
+	write_device_photos_db(&app_handle, &db)
+}
+
+/// Re-reads the on-disk database, for callers that want a fresh view after
+/// another process (or window) may have changed it.
+#[command(rename_all = "snake_case")]
+pub fn refresh_device_photos(app_handle: tauri::AppHandle) -> Result<DevicePhotosDbResponse, String> {
+	load_device_photos_db(app_handle)
+}
+
+/// Removes a single photo from the on-disk database by id.
+#[command(rename_all = "snake_case")]
+pub fn delete_device_photo(app_handle: tauri::AppHandle, photo_id: String) -> Result<(), String> {
+	let mut db = read_device_photos_db(&app_handle)?;
+	db.photos.retain(|p| p.id != photo_id);
+	db.last_updated = chrono::Utc::now().timestamp_millis();
+	write_device_photos_db(&app_handle, &db)
+}
+
+/// Adds `tag` to the photo's tag set, if not already present.
+#[command(rename_all = "snake_case")]
+pub fn add_device_photo_tag(app_handle: tauri::AppHandle, photo_id: String, tag: String) -> Result<(), String> {
+	let mut db = read_device_photos_db(&app_handle)?;
+	let photo = db
+		.photos
+		.iter_mut()
+		.find(|p| p.id == photo_id)
+		.ok_or_else(|| format!("Photo {} not found in database", photo_id))?;
+	if !photo.tags.contains(&tag) {
+		photo.tags.push(tag);
+	}
+	db.last_updated = chrono::Utc::now().timestamp_millis();
+	write_device_photos_db(&app_handle, &db)
+}
+
+/// Removes `tag` from the photo's tag set, if present.
+#[command(rename_all = "snake_case")]
+pub fn remove_device_photo_tag(app_handle: tauri::AppHandle, photo_id: String, tag: String) -> Result<(), String> {
+	let mut db = read_device_photos_db(&app_handle)?;
+	let photo = db
+		.photos
+		.iter_mut()
+		.find(|p| p.id == photo_id)
+		.ok_or_else(|| format!("Photo {} not found in database", photo_id))?;
+	photo.tags.retain(|t| t != &tag);
+	db.last_updated = chrono::Utc::now().timestamp_millis();
+	write_device_photos_db(&app_handle, &db)
+}
+
+/// Queries photos by tag. When `match_all` is true, a photo must carry every
+/// tag in `tags` (AND semantics); otherwise any matching tag is enough (OR).
+#[command(rename_all = "snake_case")]
+pub fn query_device_photos_by_tags(
+	app_handle: tauri::AppHandle,
+	tags: Vec<String>,
+	match_all: bool,
+) -> Result<Vec<DevicePhotoMetadata>, String> {
+	let db = read_device_photos_db(&app_handle)?;
+	let matches = db
+		.photos
+		.into_iter()
+		.filter(|photo| {
+			if match_all {
+				tags.iter().all(|t| photo.tags.contains(t))
+			} else {
+				tags.iter().any(|t| photo.tags.contains(t))
+			}
+		})
+		.collect();
+	Ok(matches)
+}
+
+// --- Export / import ---------------------------------------------------------
+//
+// A versioned JSON document users can copy between devices or keep as a
+// backup, independent of the server-side upload state.
+
+/// Schema version of [`DevicePhotosExport`]. Bump this whenever the shape of
+/// `DevicePhotoMetadata` changes in a way that would break older importers.
+const DEVICE_PHOTOS_EXPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DevicePhotosExport {
+	version: u32,
+	photos: Vec<DevicePhotoMetadata>,
+	last_updated: i64,
+}
+
+/// Writes the full on-disk database to `out_path` as a versioned JSON
+/// document, for backup or migration to another device.
+#[command(rename_all = "snake_case")]
+pub fn export_device_photos_db(app_handle: tauri::AppHandle, out_path: String) -> Result<(), String> {
+	let db = read_device_photos_db(&app_handle)?;
+	let export = DevicePhotosExport { version: DEVICE_PHOTOS_EXPORT_VERSION, photos: db.photos, last_updated: db.last_updated };
+	let raw = serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize export: {}", e))?;
+	std::fs::write(&out_path, raw).map_err(|e| format!("Failed to write export file {}: {}", out_path, e))
+}
+
+/// Reads a JSON document previously written by [`export_device_photos_db`]
+/// from `in_path`. When `merge` is true, imported photos are merged into the
+/// existing database by `id` (imported entries win on conflict); otherwise
+/// the existing database is replaced wholesale. Any imported photo whose
+/// file still exists on disk but isn't registered in the Android database is
+/// re-added there, so it gets picked up by the upload worker again.
+#[command(rename_all = "snake_case")]
+pub fn import_device_photos_db(
+	app_handle: tauri::AppHandle,
+	in_path: String,
+	merge: bool,
+) -> Result<DevicePhotosDbResponse, String> {
+	let raw = std::fs::read_to_string(&in_path).map_err(|e| format!("Failed to read import file {}: {}", in_path, e))?;
+	let import: DevicePhotosExport =
+		serde_json::from_str(&raw).map_err(|e| format!("Failed to parse import file {}: {}", in_path, e))?;
+
+	if import.version != DEVICE_PHOTOS_EXPORT_VERSION {
+		return Err(format!(
+			"Unsupported device photos export version {} (expected {})",
+			import.version, DEVICE_PHOTOS_EXPORT_VERSION
+		));
+	}
+
+	let mut db = if merge { read_device_photos_db(&app_handle)? } else { DevicePhotosDb::default() };
+
+	for photo in &import.photos {
+		requeue_if_missing_from_android_db(&app_handle, photo);
+	}
+
+	if merge {
+		for photo in import.photos {
+			db.photos.retain(|p| p.id != photo.id);
+			db.photos.push(photo);
+		}
+	} else {
+		db.photos = import.photos;
+	}
+	db.last_updated = chrono::Utc::now().timestamp_millis();
+
+	write_device_photos_db(&app_handle, &db)?;
+	Ok(DevicePhotosDbResponse { photos: db.photos, last_updated: db.last_updated })
+}
+
+/// Re-registers `photo` with the Android photo database if its file is still
+/// present on disk, so imports survive a device's Android database being
+/// wiped (e.g. after a reinstall) while the photo files themselves remain.
+#[cfg(target_os = "android")]
+fn requeue_if_missing_from_android_db(app_handle: &tauri::AppHandle, photo: &DevicePhotoMetadata) {
+	if !std::path::Path::new(&photo.path).exists() {
+		return;
+	}
+
+	let already_registered = app_handle
+		.hillview()
+		.get_device_photos()
+		.map(|response| response.photos.iter().any(|p| p.get("id").and_then(|id| id.as_str()) == Some(photo.id.as_str())))
+		.unwrap_or(false);
+	if already_registered {
+		return;
+	}
+
+	let plugin_photo = tauri_plugin_hillview::shared_types::DevicePhotoMetadata {
+		id: photo.id.clone(),
+		filename: photo.filename.clone(),
+		path: photo.path.clone(),
+		metadata: tauri_plugin_hillview::shared_types::PhotoMetadata {
+			latitude: photo.latitude,
+			longitude: photo.longitude,
+			altitude: photo.altitude,
+			bearing: photo.bearing,
+			captured_at: photo.captured_at,
+			accuracy: photo.accuracy,
+			location_source: String::new(),
+			bearing_source: String::new(),
+		},
+		width: photo.width,
+		height: photo.height,
+		file_size: photo.file_size,
+		file_hash: None,
+		created_at: photo.created_at,
+		blurhash: photo.blurhash.clone(),
+		thumbnail_256_path: photo.thumbnail_256_path.clone(),
+		thumbnail_1024_path: photo.thumbnail_1024_path.clone(),
+	};
+
+	match app_handle.hillview().add_photo_to_database(plugin_photo) {
+		Ok(response) if response.success => {
+			info!("📱 Re-queued imported photo {} into Android database", photo.id);
+		}
+		Ok(response) => {
+			warn!("📱 Failed to re-queue imported photo {}: {:?}", photo.id, response.error);
+		}
+		Err(e) => {
+			warn!("📱 Error re-queuing imported photo {}: {}", photo.id, e);
+		}
+	}
+}
+
+/// No Android database to reconcile against on other platforms.
+#[cfg(not(target_os = "android"))]
+fn requeue_if_missing_from_android_db(_app_handle: &tauri::AppHandle, _photo: &DevicePhotoMetadata) {}
@@ -0,0 +1,252 @@
+//! Pluggable ground-elevation backfill for photos whose GPS altitude is
+//! missing or unreliable, batched through a single multi-point DEM lookup
+//! instead of one round trip per photo.
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+use crate::device_photos::DevicePhotoMetadata;
+
+/// Value written to [`DevicePhotoMetadata::altitude_source`] when the
+/// altitude came from the elevation provider rather than GPS.
+pub const ALTITUDE_SOURCE_DEM: &str = "dem";
+
+/// A source of ground elevation for `(latitude, longitude)` points.
+/// Implemented by [`HttpElevationProvider`], which is the provider
+/// `backfill_altitudes` constructs and uses.
+#[async_trait::async_trait]
+pub trait ElevationProvider: Send + Sync {
+	/// Looks up ground elevation (meters) for each `(latitude, longitude)` in
+	/// `points`, in the same order. `None` at an index means no elevation
+	/// was available for that point.
+	async fn lookup(&self, points: &[(f64, f64)]) -> Result<Vec<Option<f64>>, String>;
+}
+
+/// Endpoint configuration for [`HttpElevationProvider`], set via
+/// `set_elevation_config`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElevationConfig {
+	/// Base URL of a batch elevation API (e.g. a self-hosted Open-Elevation
+	/// instance), POSTed a `{"locations": [{"latitude","longitude"}, ...]}`
+	/// body and expected to return `{"results": [{"elevation", ...}, ...]}`
+	/// in the same order as the request.
+	pub endpoint: String,
+}
+
+static ELEVATION_CONFIG: OnceLock<Mutex<Option<ElevationConfig>>> = OnceLock::new();
+
+fn elevation_config_slot() -> &'static Mutex<Option<ElevationConfig>> {
+	ELEVATION_CONFIG.get_or_init(|| Mutex::new(None))
+}
+
+/// Configures (or clears, via `None`) the HTTP elevation provider endpoint.
+#[tauri::command(rename_all = "snake_case")]
+pub fn set_elevation_config(config: Option<ElevationConfig>) -> Result<(), String> {
+	*elevation_config_slot().lock().map_err(|e| format!("Failed to lock elevation config: {}", e))? = config;
+	Ok(())
+}
+
+/// Returns the currently configured elevation endpoint, if any.
+pub fn elevation_config() -> Option<ElevationConfig> {
+	elevation_config_slot().lock().ok().and_then(|guard| guard.clone())
+}
+
+#[derive(Debug, Serialize)]
+struct ElevationLocation {
+	latitude: f64,
+	longitude: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct ElevationRequestBody {
+	locations: Vec<ElevationLocation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ElevationResult {
+	elevation: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ElevationResponseBody {
+	results: Vec<ElevationResult>,
+}
+
+/// Batch elevation lookup against a configured HTTP endpoint.
+pub struct HttpElevationProvider {
+	config: ElevationConfig,
+	client: reqwest::Client,
+}
+
+impl HttpElevationProvider {
+	pub fn new(config: ElevationConfig) -> Self {
+		Self { config, client: reqwest::Client::new() }
+	}
+}
+
+#[async_trait::async_trait]
+impl ElevationProvider for HttpElevationProvider {
+	async fn lookup(&self, points: &[(f64, f64)]) -> Result<Vec<Option<f64>>, String> {
+		if points.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let body = ElevationRequestBody {
+			locations: points.iter().map(|(lat, lon)| ElevationLocation { latitude: *lat, longitude: *lon }).collect(),
+		};
+
+		let response = self
+			.client
+			.post(&self.config.endpoint)
+			.json(&body)
+			.send()
+			.await
+			.map_err(|e| format!("Elevation request failed: {}", e))?;
+
+		if !response.status().is_success() {
+			return Err(format!("Elevation endpoint returned status {}", response.status()));
+		}
+
+		let parsed: ElevationResponseBody =
+			response.json().await.map_err(|e| format!("Failed to parse elevation response: {}", e))?;
+
+		if parsed.results.len() != points.len() {
+			return Err(format!(
+				"Elevation endpoint returned {} results for {} points",
+				parsed.results.len(),
+				points.len()
+			));
+		}
+
+		Ok(parsed.results.into_iter().map(|r| r.elevation).collect())
+	}
+}
+
+/// How many decimal degrees of rounding [`ElevationCache`] keys its lookups
+/// by; 4 decimal places is about 11m, tight enough to be useful for
+/// photos taken from roughly the same spot.
+const CACHE_COORDINATE_PRECISION: i32 = 4;
+
+/// Bounds how many rounded-coordinate entries [`ElevationCache`] keeps before
+/// evicting the least-recently-used one.
+const CACHE_CAPACITY: usize = 2048;
+
+/// In-memory LRU cache of elevation lookups keyed by rounded coordinates, so
+/// repeated backfills (or photos clustered at the same spot) don't re-hit
+/// the elevation endpoint.
+struct ElevationCache {
+	entries: HashMap<(i64, i64), Option<f64>>,
+	order: std::collections::VecDeque<(i64, i64)>,
+}
+
+impl ElevationCache {
+	fn new() -> Self {
+		Self { entries: HashMap::new(), order: std::collections::VecDeque::new() }
+	}
+
+	fn key(lat: f64, lon: f64) -> (i64, i64) {
+		let scale = 10f64.powi(CACHE_COORDINATE_PRECISION);
+		((lat * scale).round() as i64, (lon * scale).round() as i64)
+	}
+
+	fn get(&mut self, lat: f64, lon: f64) -> Option<Option<f64>> {
+		let key = Self::key(lat, lon);
+		if self.entries.contains_key(&key) {
+			self.order.retain(|k| k != &key);
+			self.order.push_back(key);
+			self.entries.get(&key).copied()
+		} else {
+			None
+		}
+	}
+
+	fn insert(&mut self, lat: f64, lon: f64, elevation: Option<f64>) {
+		let key = Self::key(lat, lon);
+		if !self.entries.contains_key(&key) {
+			while self.entries.len() >= CACHE_CAPACITY {
+				if let Some(oldest) = self.order.pop_front() {
+					self.entries.remove(&oldest);
+				} else {
+					break;
+				}
+			}
+		} else {
+			self.order.retain(|k| k != &key);
+		}
+		self.entries.insert(key, elevation);
+		self.order.push_back(key);
+	}
+}
+
+static ELEVATION_CACHE: OnceLock<Mutex<ElevationCache>> = OnceLock::new();
+
+fn elevation_cache() -> &'static Mutex<ElevationCache> {
+	ELEVATION_CACHE.get_or_init(|| Mutex::new(ElevationCache::new()))
+}
+
+/// Batches all `photos` missing `altitude` into a single multi-point
+/// elevation query (after checking the LRU cache), writing the looked-up
+/// ground elevation back into each matching record along with
+/// `altitude_source`. Photos that already have an altitude, or for which no
+/// elevation was found, are left untouched.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn backfill_altitudes(mut photos: Vec<DevicePhotoMetadata>) -> Result<Vec<DevicePhotoMetadata>, String> {
+	let config = elevation_config().ok_or_else(|| "No elevation provider configured".to_string())?;
+	let provider = HttpElevationProvider::new(config);
+
+	let missing_indices: Vec<usize> = photos.iter().enumerate().filter(|(_, p)| p.altitude.is_none()).map(|(i, _)| i).collect();
+	if missing_indices.is_empty() {
+		return Ok(photos);
+	}
+
+	let mut to_fetch: Vec<(f64, f64)> = Vec::new();
+	let mut fetch_indices: Vec<usize> = Vec::new();
+	let mut cached: HashMap<usize, Option<f64>> = HashMap::new();
+
+	{
+		let mut cache = elevation_cache().lock().map_err(|e| format!("Failed to lock elevation cache: {}", e))?;
+		for &idx in &missing_indices {
+			let (lat, lon) = (photos[idx].latitude, photos[idx].longitude);
+			match cache.get(lat, lon) {
+				Some(elevation) => {
+					cached.insert(idx, elevation);
+				}
+				None => {
+					fetch_indices.push(idx);
+					to_fetch.push((lat, lon));
+				}
+			}
+		}
+	}
+
+	if !to_fetch.is_empty() {
+		let fetched = provider.lookup(&to_fetch).await?;
+		if fetched.len() != to_fetch.len() {
+			return Err(format!("Elevation provider returned {} results for {} points", fetched.len(), to_fetch.len()));
+		}
+
+		let mut cache = elevation_cache().lock().map_err(|e| format!("Failed to lock elevation cache: {}", e))?;
+		for (&idx, &elevation) in fetch_indices.iter().zip(fetched.iter()) {
+			let (lat, lon) = (photos[idx].latitude, photos[idx].longitude);
+			cache.insert(lat, lon, elevation);
+			cached.insert(idx, elevation);
+		}
+	}
+
+	for idx in missing_indices {
+		match cached.get(&idx).copied().flatten() {
+			Some(elevation) => {
+				photos[idx].altitude = Some(elevation);
+				photos[idx].altitude_source = Some(ALTITUDE_SOURCE_DEM.to_string());
+			}
+			None => {
+				warn!("🌍 No elevation found for photo {} at ({}, {})", photos[idx].id, photos[idx].latitude, photos[idx].longitude);
+			}
+		}
+	}
+
+	Ok(photos)
+}
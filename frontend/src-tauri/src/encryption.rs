@@ -0,0 +1,118 @@
+//! Optional at-rest encryption for photos saved with `hide_from_gallery`.
+//!
+//! A `.nomedia` marker keeps a photo out of the system gallery, but the JPEG
+//! bytes are still plaintext on shared storage. When a vault key has been
+//! set via [`store_vault_key`], hidden photos are instead encrypted with
+//! XChaCha20Poly1305 and written with an `.hvenc` extension so the gallery
+//! scanner (and any other EXIF/image tooling) skips them outright.
+
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use tauri::Manager;
+
+/// Extension appended to vault-encrypted photos so the gallery scanner and
+/// EXIF readers can recognize and skip them.
+pub const VAULT_EXTENSION: &str = "hvenc";
+
+const NONCE_LEN: usize = 24;
+const SALT_LEN: usize = 16;
+
+static VAULT_KEY: OnceLock<Mutex<Option<[u8; 32]>>> = OnceLock::new();
+
+fn vault_key_slot() -> &'static Mutex<Option<[u8; 32]>> {
+	VAULT_KEY.get_or_init(|| Mutex::new(None))
+}
+
+fn vault_salt_path(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+	let dir = app_handle.path().app_data_dir().map_err(|e| format!("Failed to resolve app data dir: {}", e))?;
+	std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data dir: {}", e))?;
+	Ok(dir.join("vault_salt"))
+}
+
+/// Loads this device's persisted vault salt, generating and saving a new
+/// random one on first use. The salt isn't secret - it just needs to be
+/// stable per-install so the same passphrase always derives the same key.
+fn load_or_create_vault_salt(app_handle: &tauri::AppHandle) -> Result<[u8; SALT_LEN], String> {
+	let path = vault_salt_path(app_handle)?;
+
+	if path.exists() {
+		let raw = std::fs::read(&path).map_err(|e| format!("Failed to read vault salt: {}", e))?;
+		return raw.try_into().map_err(|_| "Corrupt vault salt: wrong length".to_string());
+	}
+
+	let mut salt = [0u8; SALT_LEN];
+	rand::thread_rng().fill_bytes(&mut salt);
+	std::fs::write(&path, salt).map_err(|e| format!("Failed to write vault salt: {}", e))?;
+	Ok(salt)
+}
+
+/// Derives a 32-byte vault key from a user passphrase with Argon2id, using a
+/// per-install salt persisted alongside the app data, and stores the result
+/// in memory for the lifetime of the process. Argon2id's memory-hard
+/// stretching (unlike a single unsalted hash) makes brute-forcing the key
+/// from a weak passphrase impractical. In a production build the passphrase
+/// itself should come from the same secure-storage path used by
+/// `store_auth_token`, not be kept around longer than needed.
+#[tauri::command]
+pub fn store_vault_key(app_handle: tauri::AppHandle, passphrase: String) -> Result<(), String> {
+	if passphrase.is_empty() {
+		return Err("Vault passphrase must not be empty".to_string());
+	}
+
+	let salt = load_or_create_vault_salt(&app_handle)?;
+
+	let mut key = [0u8; 32];
+	Argon2::default()
+		.hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+		.map_err(|e| format!("Failed to derive vault key: {}", e))?;
+
+	*vault_key_slot()
+		.lock()
+		.map_err(|e| format!("Failed to lock vault key: {}", e))? = Some(key);
+
+	Ok(())
+}
+
+/// Returns the currently configured vault key, if any. Hidden photos are
+/// only encrypted when a key has been set - otherwise they fall back to the
+/// existing plaintext-plus-`.nomedia` behavior.
+pub fn vault_key() -> Option<[u8; 32]> {
+	vault_key_slot().lock().ok().and_then(|guard| *guard)
+}
+
+/// Encrypts `plaintext` with XChaCha20Poly1305, returning `nonce || ciphertext`.
+pub fn encrypt(plaintext: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, String> {
+	let cipher = XChaCha20Poly1305::new(key.into());
+
+	let mut nonce_bytes = [0u8; NONCE_LEN];
+	rand::thread_rng().fill_bytes(&mut nonce_bytes);
+	let nonce = XNonce::from_slice(&nonce_bytes);
+
+	let ciphertext = cipher
+		.encrypt(nonce, plaintext)
+		.map_err(|e| format!("Failed to encrypt photo: {}", e))?;
+
+	let mut out = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+	out.extend_from_slice(&nonce_bytes);
+	out.extend_from_slice(&ciphertext);
+	Ok(out)
+}
+
+/// Decrypts data previously produced by [`encrypt`].
+pub fn decrypt(data: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, String> {
+	if data.len() < NONCE_LEN {
+		return Err("Encrypted photo is too short to contain a nonce".to_string());
+	}
+
+	let (nonce_bytes, ciphertext) = data.split_at(NONCE_LEN);
+	let nonce = XNonce::from_slice(nonce_bytes);
+	let cipher = XChaCha20Poly1305::new(key.into());
+
+	cipher
+		.decrypt(nonce, ciphertext)
+		.map_err(|e| format!("Failed to decrypt photo: {}", e))
+}
@@ -0,0 +1,47 @@
+//! Minimal geohash encoder (https://en.wikipedia.org/wiki/Geohash), without
+//! pulling in an extra crate for something this small.
+
+const BASE32_CHARS: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Encodes `(latitude, longitude)` into a base32 geohash string of `precision`
+/// characters. Higher precision means a smaller covered area; 7 characters
+/// (the default used for dedup) covers roughly a 150m x 150m cell.
+pub fn encode(latitude: f64, longitude: f64, precision: usize) -> String {
+	let mut lat_range = (-90.0, 90.0);
+	let mut lon_range = (-180.0, 180.0);
+	let mut is_even = true;
+	let mut bit = 0u8;
+	let mut ch = 0u8;
+	let mut geohash = String::with_capacity(precision);
+
+	while geohash.len() < precision {
+		if is_even {
+			let mid = (lon_range.0 + lon_range.1) / 2.0;
+			if longitude >= mid {
+				ch |= 1 << (4 - bit);
+				lon_range.0 = mid;
+			} else {
+				lon_range.1 = mid;
+			}
+		} else {
+			let mid = (lat_range.0 + lat_range.1) / 2.0;
+			if latitude >= mid {
+				ch |= 1 << (4 - bit);
+				lat_range.0 = mid;
+			} else {
+				lat_range.1 = mid;
+			}
+		}
+
+		is_even = !is_even;
+		if bit < 4 {
+			bit += 1;
+		} else {
+			geohash.push(BASE32_CHARS[ch as usize] as char);
+			bit = 0;
+			ch = 0;
+		}
+	}
+
+	geohash
+}
@@ -0,0 +1,106 @@
+//! GPX 1.1 export for geotagged device photos (https://www.topografix.com/gpx.asp),
+//! so a capture session can be archived or opened directly in mapping tools.
+
+use tauri::command;
+
+use crate::device_photos::DevicePhotoMetadata;
+
+/// XML namespace used for the Hillview-specific extension elements
+/// (`<hillview:bearing>`) carried on each waypoint.
+const HILLVIEW_XMLNS: &str = "https://github.com/koo5/hillview/gpx-extensions/v1";
+
+/// Serializes `photos` into a GPX 1.1 document: one `<wpt>` per photo (with
+/// `<ele>` from `altitude`, `<time>` from `captured_at`, `<name>` from
+/// `filename`, and `bearing` carried in a `hillview:` extension) plus a single
+/// `<trk>/<trkseg>` ordering the same points by `captured_at`, so the capture
+/// path can be loaded into mapping tools alongside the individual waypoints.
+pub fn build_gpx(photos: &[DevicePhotoMetadata]) -> String {
+	let mut ordered: Vec<&DevicePhotoMetadata> = photos.iter().collect();
+	ordered.sort_by_key(|photo| photo.captured_at);
+
+	let mut gpx = String::new();
+	gpx.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+	gpx.push_str(&format!(
+		"<gpx version=\"1.1\" creator=\"Hillview\" xmlns=\"http://www.topografix.com/GPX/1/1\" xmlns:hillview=\"{}\">\n",
+		HILLVIEW_XMLNS
+	));
+
+	for photo in photos {
+		gpx.push_str(&waypoint_xml(photo));
+	}
+
+	if !ordered.is_empty() {
+		gpx.push_str("  <trk>\n    <name>Hillview capture path</name>\n    <trkseg>\n");
+		for photo in &ordered {
+			gpx.push_str(&trackpoint_xml(photo));
+		}
+		gpx.push_str("    </trkseg>\n  </trk>\n");
+	}
+
+	gpx.push_str("</gpx>\n");
+	gpx
+}
+
+fn waypoint_xml(photo: &DevicePhotoMetadata) -> String {
+	format!(
+		"  <wpt lat=\"{lat}\" lon=\"{lon}\">\n{ele}    <time>{time}</time>\n    <name>{name}</name>\n{ext}  </wpt>\n",
+		lat = photo.latitude,
+		lon = photo.longitude,
+		ele = elevation_xml(photo.altitude, "    "),
+		time = format_rfc3339(photo.captured_at),
+		name = xml_escape(&photo.filename),
+		ext = bearing_extension_xml(photo.bearing, "    "),
+	)
+}
+
+fn trackpoint_xml(photo: &DevicePhotoMetadata) -> String {
+	format!(
+		"      <trkpt lat=\"{lat}\" lon=\"{lon}\">\n{ele}        <time>{time}</time>\n      </trkpt>\n",
+		lat = photo.latitude,
+		lon = photo.longitude,
+		ele = elevation_xml(photo.altitude, "        "),
+		time = format_rfc3339(photo.captured_at),
+	)
+}
+
+fn elevation_xml(altitude: Option<f64>, indent: &str) -> String {
+	match altitude {
+		Some(alt) => format!("{indent}<ele>{alt}</ele>\n"),
+		None => String::new(),
+	}
+}
+
+/// Emits the bearing as a `<hillview:bearing>` extension element. There's no
+/// `bearing_source` on [`DevicePhotoMetadata`] (it only lives on the
+/// in-flight [`crate::types::PhotoMetadata`]), so `<hillview:bearingSource>`
+/// is omitted here rather than guessed at.
+fn bearing_extension_xml(bearing: Option<f64>, indent: &str) -> String {
+	match bearing {
+		Some(bearing) => {
+			format!("{indent}<extensions>\n{indent}  <hillview:bearing>{bearing}</hillview:bearing>\n{indent}</extensions>\n")
+		}
+		None => String::new(),
+	}
+}
+
+fn format_rfc3339(captured_at: i64) -> String {
+	chrono::DateTime::from_timestamp(captured_at, 0)
+		.unwrap_or_else(|| chrono::Utc::now())
+		.format("%Y-%m-%dT%H:%M:%SZ")
+		.to_string()
+}
+
+fn xml_escape(value: &str) -> String {
+	value
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+		.replace('\'', "&apos;")
+}
+
+/// Serializes `photos` into a GPX 1.1 document for export/sharing.
+#[command(rename_all = "snake_case")]
+pub fn export_photos_gpx(photos: Vec<DevicePhotoMetadata>) -> Result<String, String> {
+	Ok(build_gpx(&photos))
+}
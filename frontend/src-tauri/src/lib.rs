@@ -1,6 +1,15 @@
+mod blurhash;
 mod commands;
+mod declination;
 mod device_photos;
+mod elevation;
+mod encryption;
+mod geohash;
+mod gpx_export;
+mod p2p_sync;
 mod photo_exif;
+mod upload;
+mod xmp;
 use log::info;
 #[cfg(debug_assertions)]
 use tauri::Manager;
@@ -41,11 +50,25 @@ pub fn run() {
             photo_exif::save_photo_with_metadata,
             photo_exif::read_device_photo,
             photo_exif::read_photo_exif,
+            encryption::store_vault_key,
+            upload::set_s3_upload_config,
             device_photos::load_device_photos_db,
             device_photos::save_device_photos_db,
             device_photos::add_device_photo_to_db,
             device_photos::refresh_device_photos,
-            device_photos::delete_device_photo
+            device_photos::delete_device_photo,
+            device_photos::add_device_photo_tag,
+            device_photos::remove_device_photo_tag,
+            device_photos::query_device_photos_by_tags,
+            device_photos::export_device_photos_db,
+            device_photos::import_device_photos_db,
+            gpx_export::export_photos_gpx,
+            elevation::set_elevation_config,
+            elevation::backfill_altitudes,
+            p2p_sync::generate_pairing_code,
+            p2p_sync::accept_pairing,
+            p2p_sync::list_peers,
+            p2p_sync::sync_metadata
         ])
         .setup(|app| {
             // Tauri log plugin disabled to prevent duplicate console logs
@@ -0,0 +1,401 @@
+//! Peer-to-peer photo-metadata sync, as an alternative to the upload server
+//! for keeping a user's own devices consistent. Two installs pair by
+//! exchanging a [`NodeInformation`] + listen address out of band (QR code,
+//! short text code) rather than through any coordinating server; once
+//! paired, `sync_metadata` reconciles `DevicePhotoMetadata` sets over a
+//! small hand-rolled protocol authenticated and encrypted with a shared key
+//! derived from an X25519 key agreement between the two devices.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use log::warn;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use tauri::command;
+use tauri::Manager;
+use tauri_plugin_hillview::DevicePhotosResponse;
+
+use crate::device_photos::{self, DevicePhotoMetadata};
+use crate::encryption;
+
+/// This device's stable identity: a random id, a user-chosen display name,
+/// and the X25519 public key exchanged with peers during pairing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeInformation {
+	pub device_id: String,
+	pub device_name: String,
+	/// X25519 public key, base64-encoded.
+	pub public_key: String,
+}
+
+/// On-disk identity record; `secret_key` never leaves this device.
+#[derive(Debug, Serialize, Deserialize)]
+struct NodeIdentity {
+	info: NodeInformation,
+	secret_key: String,
+}
+
+fn identity_path(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+	let dir = app_handle.path().app_data_dir().map_err(|e| format!("Failed to resolve app data dir: {}", e))?;
+	std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data dir: {}", e))?;
+	Ok(dir.join("p2p_identity.json"))
+}
+
+fn random_device_id() -> String {
+	let mut bytes = [0u8; 16];
+	rand::thread_rng().fill_bytes(&mut bytes);
+	bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Loads this device's persisted X25519 identity, generating and saving a
+/// new one on first use. `device_name`, if non-empty, renames an existing
+/// identity (the user can rename their device from any future pairing).
+fn load_or_create_identity(
+	app_handle: &tauri::AppHandle,
+	device_name: Option<&str>,
+) -> Result<(NodeInformation, x25519_dalek::StaticSecret), String> {
+	let path = identity_path(app_handle)?;
+
+	if path.exists() {
+		let raw = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read p2p identity: {}", e))?;
+		let mut identity: NodeIdentity = serde_json::from_str(&raw).map_err(|e| format!("Failed to parse p2p identity: {}", e))?;
+
+		if let Some(name) = device_name {
+			if !name.is_empty() && name != identity.info.device_name {
+				identity.info.device_name = name.to_string();
+				let raw = serde_json::to_string_pretty(&identity).map_err(|e| format!("Failed to serialize p2p identity: {}", e))?;
+				std::fs::write(&path, raw).map_err(|e| format!("Failed to write p2p identity: {}", e))?;
+			}
+		}
+
+		let secret_bytes: [u8; 32] = BASE64
+			.decode(&identity.secret_key)
+			.map_err(|e| format!("Corrupt p2p identity: {}", e))?
+			.try_into()
+			.map_err(|_| "Corrupt p2p identity: secret key has the wrong length".to_string())?;
+		return Ok((identity.info, x25519_dalek::StaticSecret::from(secret_bytes)));
+	}
+
+	let secret = x25519_dalek::StaticSecret::new(&mut rand::thread_rng());
+	let public = x25519_dalek::PublicKey::from(&secret);
+	let info = NodeInformation {
+		device_id: random_device_id(),
+		device_name: device_name.filter(|n| !n.is_empty()).unwrap_or("Hillview Device").to_string(),
+		public_key: BASE64.encode(public.as_bytes()),
+	};
+	let identity = NodeIdentity { info: info.clone(), secret_key: BASE64.encode(secret.to_bytes()) };
+	let raw = serde_json::to_string_pretty(&identity).map_err(|e| format!("Failed to serialize p2p identity: {}", e))?;
+	std::fs::write(&path, raw).map_err(|e| format!("Failed to write p2p identity: {}", e))?;
+	Ok((info, secret))
+}
+
+/// A paired remote device, with the address needed to reach it for
+/// `sync_metadata`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Peer {
+	info: NodeInformation,
+	address: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PeerList {
+	peers: Vec<Peer>,
+}
+
+fn peers_path(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+	let dir = app_handle.path().app_data_dir().map_err(|e| format!("Failed to resolve app data dir: {}", e))?;
+	Ok(dir.join("p2p_peers.json"))
+}
+
+fn read_peers(app_handle: &tauri::AppHandle) -> Result<PeerList, String> {
+	let path = peers_path(app_handle)?;
+	if !path.exists() {
+		return Ok(PeerList::default());
+	}
+	let raw = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read peer list: {}", e))?;
+	serde_json::from_str(&raw).map_err(|e| format!("Failed to parse peer list: {}", e))
+}
+
+fn write_peers(app_handle: &tauri::AppHandle, peers: &PeerList) -> Result<(), String> {
+	let path = peers_path(app_handle)?;
+	let raw = serde_json::to_string_pretty(peers).map_err(|e| format!("Failed to serialize peer list: {}", e))?;
+	std::fs::write(&path, raw).map_err(|e| format!("Failed to write peer list: {}", e))
+}
+
+fn upsert_peer(app_handle: &tauri::AppHandle, peer: Peer) -> Result<(), String> {
+	let mut list = read_peers(app_handle)?;
+	list.peers.retain(|p| p.info.device_id != peer.info.device_id);
+	list.peers.push(peer);
+	write_peers(app_handle, &list)
+}
+
+/// What `generate_pairing_code` encodes and `accept_pairing`/the handshake
+/// decode: a device's identity plus the address it listens for sync
+/// connections on. Meant to travel out of band (QR code, short text code),
+/// never over the sync connection itself. `nonce` is the pairing challenge:
+/// the side that generated the code remembers it in `pending_pairing_codes`
+/// and only trusts an incoming `HELLO` that echoes it back, so a device that
+/// merely reaches the listening port without having seen the scanned/typed
+/// code can't get itself added to the trusted peer list.
+#[derive(Debug, Serialize, Deserialize)]
+struct PairingTicket {
+	node: NodeInformation,
+	address: String,
+	nonce: String,
+}
+
+/// Pairing codes this device has generated via `generate_pairing_code` and is
+/// still waiting to see echoed back in a `HELLO`. Entries are consumed (and
+/// thus can't be replayed) the first time a matching handshake arrives.
+static PENDING_PAIRING_CODES: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+fn pending_pairing_codes() -> &'static Mutex<HashSet<String>> {
+	PENDING_PAIRING_CODES.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+fn random_nonce() -> String {
+	let mut bytes = [0u8; 16];
+	rand::thread_rng().fill_bytes(&mut bytes);
+	bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Derives the shared XChaCha20Poly1305 key for talking to the peer whose
+/// base64 X25519 public key is `peer_public_key`, via Diffie-Hellman plus a
+/// SHA-256 pass so the key has uniform entropy the way a KDF output would.
+fn shared_key(secret: &x25519_dalek::StaticSecret, peer_public_key: &str) -> Result<[u8; 32], String> {
+	let bytes: [u8; 32] = BASE64
+		.decode(peer_public_key)
+		.map_err(|e| format!("Invalid peer public key: {}", e))?
+		.try_into()
+		.map_err(|_| "Invalid peer public key: wrong length".to_string())?;
+	let public = x25519_dalek::PublicKey::from(bytes);
+	let agreed = secret.diffie_hellman(&public);
+
+	let mut hasher = Sha256::new();
+	hasher.update(agreed.as_bytes());
+	let digest = hasher.finalize();
+	let mut key = [0u8; 32];
+	key.copy_from_slice(&digest);
+	Ok(key)
+}
+
+/// The encrypted half of the sync protocol, exchanged after the cleartext
+/// `SYNC` + sender-id header has let the receiver derive the shared key.
+#[derive(Debug, Serialize, Deserialize)]
+enum SyncMessage {
+	MetadataRequest,
+	MetadataResponse { photos: Vec<DevicePhotoMetadata> },
+}
+
+fn encrypt_message(key: &[u8; 32], message: &SyncMessage) -> Result<Vec<u8>, String> {
+	let plaintext = serde_json::to_vec(message).map_err(|e| format!("Failed to encode sync message: {}", e))?;
+	encryption::encrypt(&plaintext, key)
+}
+
+fn decrypt_message(key: &[u8; 32], data: &[u8]) -> Result<SyncMessage, String> {
+	let plaintext = encryption::decrypt(data, key)?;
+	serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to decode sync message: {}", e))
+}
+
+fn write_frame(stream: &mut TcpStream, bytes: &[u8]) -> Result<(), String> {
+	let len = bytes.len() as u32;
+	stream.write_all(&len.to_le_bytes()).map_err(|e| format!("Failed to write frame length: {}", e))?;
+	stream.write_all(bytes).map_err(|e| format!("Failed to write frame: {}", e))
+}
+
+fn read_frame(stream: &mut TcpStream) -> Result<Vec<u8>, String> {
+	let mut len_bytes = [0u8; 4];
+	stream.read_exact(&mut len_bytes).map_err(|e| format!("Failed to read frame length: {}", e))?;
+	let len = u32::from_le_bytes(len_bytes) as usize;
+	let mut buf = vec![0u8; len];
+	stream.read_exact(&mut buf).map_err(|e| format!("Failed to read frame: {}", e))?;
+	Ok(buf)
+}
+
+/// Whether [`ensure_sync_server`] has already spawned the listener thread
+/// for this process, so pairing twice doesn't try to bind the port again.
+static SYNC_SERVER_STARTED: OnceLock<Mutex<bool>> = OnceLock::new();
+
+/// Starts the background TCP listener other devices connect to for the
+/// `HELLO` handshake and `SYNC` metadata exchange, unless one is already
+/// running for this process.
+fn ensure_sync_server(app_handle: tauri::AppHandle, listen_address: String, secret: x25519_dalek::StaticSecret) {
+	let started = SYNC_SERVER_STARTED.get_or_init(|| Mutex::new(false));
+	let mut guard = match started.lock() {
+		Ok(guard) => guard,
+		Err(_) => return,
+	};
+	if *guard {
+		return;
+	}
+	*guard = true;
+	drop(guard);
+
+	std::thread::spawn(move || {
+		let listener = match TcpListener::bind(&listen_address) {
+			Ok(listener) => listener,
+			Err(e) => {
+				warn!("🔗 P2P sync server failed to bind {}: {}", listen_address, e);
+				return;
+			}
+		};
+
+		for stream in listener.incoming() {
+			let Ok(stream) = stream else { continue };
+			let app_handle = app_handle.clone();
+			let secret = secret.clone();
+			std::thread::spawn(move || {
+				if let Err(e) = handle_incoming(stream, &app_handle, &secret) {
+					warn!("🔗 P2P sync connection failed: {}", e);
+				}
+			});
+		}
+	});
+}
+
+fn handle_incoming(mut stream: TcpStream, app_handle: &tauri::AppHandle, secret: &x25519_dalek::StaticSecret) -> Result<(), String> {
+	let kind = read_frame(&mut stream)?;
+	match kind.as_slice() {
+		b"HELLO" => {
+			let payload = read_frame(&mut stream)?;
+			let ticket: PairingTicket = serde_json::from_slice(&payload).map_err(|e| format!("Invalid handshake: {}", e))?;
+
+			let mut pending = pending_pairing_codes().lock().map_err(|_| "Pairing state poisoned".to_string())?;
+			if !pending.remove(&ticket.nonce) {
+				return Err(format!("Rejected HELLO from {}: no matching pairing code pending", ticket.node.device_id));
+			}
+			drop(pending);
+
+			upsert_peer(app_handle, Peer { info: ticket.node, address: ticket.address })
+		}
+		b"SYNC" => {
+			let sender_id = String::from_utf8(read_frame(&mut stream)?).map_err(|e| format!("Invalid sender id: {}", e))?;
+			let peer = read_peers(app_handle)?
+				.peers
+				.into_iter()
+				.find(|p| p.info.device_id == sender_id)
+				.ok_or_else(|| format!("Unknown peer {}, pair before syncing", sender_id))?;
+			let key = shared_key(secret, &peer.info.public_key)?;
+
+			let request = decrypt_message(&key, &read_frame(&mut stream)?)?;
+			if !matches!(request, SyncMessage::MetadataRequest) {
+				return Err("Expected a metadata request".to_string());
+			}
+
+			let local_photos = device_photos::load_device_photos_db(app_handle.clone())?.photos;
+			let response = encrypt_message(&key, &SyncMessage::MetadataResponse { photos: local_photos })?;
+			write_frame(&mut stream, &response)
+		}
+		_ => Err("Unknown P2P message kind".to_string()),
+	}
+}
+
+/// Generates a pairing code embedding this device's [`NodeInformation`] and
+/// `listen_address`, meant to be shown as a QR code or short text for
+/// another install to consume via `accept_pairing`. Also starts (if not
+/// already running) the background sync server peers connect to.
+#[command(rename_all = "snake_case")]
+pub fn generate_pairing_code(app_handle: tauri::AppHandle, device_name: String, listen_address: String) -> Result<String, String> {
+	let (node, secret) = load_or_create_identity(&app_handle, Some(&device_name))?;
+	ensure_sync_server(app_handle, listen_address.clone(), secret);
+
+	let nonce = random_nonce();
+	pending_pairing_codes().lock().map_err(|_| "Pairing state poisoned".to_string())?.insert(nonce.clone());
+
+	let ticket = PairingTicket { node, address: listen_address, nonce };
+	let json = serde_json::to_vec(&ticket).map_err(|e| format!("Failed to encode pairing ticket: {}", e))?;
+	Ok(BASE64.encode(json))
+}
+
+/// Decodes a pairing `code` from [`generate_pairing_code`] and adds the
+/// encoded device to the trusted peer list. Also starts this device's own
+/// sync server at `listen_address` and sends a handshake to the peer, so it
+/// learns about this device too without needing its own code scanned back.
+#[command(rename_all = "snake_case")]
+pub fn accept_pairing(
+	app_handle: tauri::AppHandle,
+	code: String,
+	device_name: String,
+	listen_address: String,
+) -> Result<NodeInformation, String> {
+	let json = BASE64.decode(code.trim()).map_err(|e| format!("Invalid pairing code: {}", e))?;
+	let ticket: PairingTicket = serde_json::from_slice(&json).map_err(|e| format!("Invalid pairing code: {}", e))?;
+
+	let (own_node, secret) = load_or_create_identity(&app_handle, Some(&device_name))?;
+	ensure_sync_server(app_handle.clone(), listen_address.clone(), secret);
+	upsert_peer(&app_handle, Peer { info: ticket.node.clone(), address: ticket.address.clone() })?;
+
+	if let Err(e) = send_handshake(&ticket.address, &own_node, &listen_address, &ticket.nonce) {
+		warn!("🔗 Paired with {} but the return handshake failed ({}); ask them to accept this device's code too", ticket.node.device_id, e);
+	}
+
+	Ok(ticket.node)
+}
+
+/// Sends the return `HELLO`, echoing back `nonce` from the scanned pairing
+/// code so the other end can confirm this handshake is the one it's waiting
+/// for rather than an unsolicited connection.
+fn send_handshake(address: &str, own_node: &NodeInformation, own_listen_address: &str, nonce: &str) -> Result<(), String> {
+	let mut stream = TcpStream::connect(address).map_err(|e| format!("Failed to connect to {}: {}", address, e))?;
+	write_frame(&mut stream, b"HELLO")?;
+	let ticket = PairingTicket { node: own_node.clone(), address: own_listen_address.to_string(), nonce: nonce.to_string() };
+	let payload = serde_json::to_vec(&ticket).map_err(|e| format!("Failed to encode handshake: {}", e))?;
+	write_frame(&mut stream, &payload)
+}
+
+/// Returns the trusted peer list built up by `generate_pairing_code`/
+/// `accept_pairing`.
+#[command(rename_all = "snake_case")]
+pub fn list_peers(app_handle: tauri::AppHandle) -> Result<Vec<NodeInformation>, String> {
+	Ok(read_peers(&app_handle)?.peers.into_iter().map(|p| p.info).collect())
+}
+
+/// Connects to `peer_id` (already in the trusted peer list) and exchanges
+/// photo metadata over the encrypted sync protocol, returning only the
+/// photos present on the peer but not found locally (matched by
+/// `file_hash`, falling back to `id`), in the same response shape as a
+/// server sync so the UI can treat the two uniformly.
+#[command(rename_all = "snake_case")]
+pub fn sync_metadata(app_handle: tauri::AppHandle, peer_id: String) -> Result<DevicePhotosResponse, String> {
+	let (own_node, secret) = load_or_create_identity(&app_handle, None)?;
+	let peer = read_peers(&app_handle)?
+		.peers
+		.into_iter()
+		.find(|p| p.info.device_id == peer_id)
+		.ok_or_else(|| format!("Peer {} is not paired with this device", peer_id))?;
+
+	let key = shared_key(&secret, &peer.info.public_key)?;
+
+	let mut stream = TcpStream::connect(&peer.address).map_err(|e| format!("Failed to connect to peer {}: {}", peer_id, e))?;
+	write_frame(&mut stream, b"SYNC")?;
+	write_frame(&mut stream, own_node.device_id.as_bytes())?;
+	write_frame(&mut stream, &encrypt_message(&key, &SyncMessage::MetadataRequest)?)?;
+
+	let response = decrypt_message(&key, &read_frame(&mut stream)?)?;
+	let remote_photos = match response {
+		SyncMessage::MetadataResponse { photos } => photos,
+		_ => return Err("Unexpected response from peer".to_string()),
+	};
+
+	let local_photos = device_photos::load_device_photos_db(app_handle.clone())?.photos;
+	let local_hashes: std::collections::HashSet<&str> = local_photos.iter().filter_map(|p| p.file_hash.as_deref()).collect();
+	let local_ids: std::collections::HashSet<&str> = local_photos.iter().map(|p| p.id.as_str()).collect();
+
+	let missing: Vec<serde_json::Value> = remote_photos
+		.into_iter()
+		.filter(|p| {
+			let already_local = p.file_hash.as_deref().map(|h| local_hashes.contains(h)).unwrap_or(false) || local_ids.contains(p.id.as_str());
+			!already_local
+		})
+		.filter_map(|p| serde_json::to_value(&p).ok())
+		.collect();
+
+	Ok(DevicePhotosResponse { photos: missing, last_updated: chrono::Utc::now().timestamp_millis() })
+}
@@ -23,6 +23,188 @@ pub struct LocationUpdate {
     pub longitude: f64,
 }
 
+/// Result of a [`SensorHistory::lookup_bearing`] query.
+#[derive(Debug, Serialize, Clone)]
+pub struct BearingLookupResult {
+    pub found: bool,
+    pub sample: Option<SensorData>,
+}
+
+/// How many past samples [`SensorHistory`] keeps before dropping the oldest.
+const SENSOR_HISTORY_CAPACITY: usize = 512;
+
+/// How far outside the buffer's time range a query is still allowed to fall
+/// back to the nearest sample, in milliseconds, before `lookup_bearing`
+/// reports `found: false`.
+const LOOKUP_TOLERANCE_MS: u64 = 2000;
+
+/// Fixed-capacity, timestamp-ordered circular buffer of recent [`SensorData`]
+/// samples, queried by [`lookup_bearing`] to resolve the bearing for a photo
+/// captured between two sensor readings.
+struct SensorHistory {
+    samples: std::collections::VecDeque<SensorData>,
+}
+
+impl SensorHistory {
+    fn new() -> Self {
+        Self { samples: std::collections::VecDeque::with_capacity(SENSOR_HISTORY_CAPACITY) }
+    }
+
+    /// Inserts `sample` in timestamp order, evicting the oldest sample once
+    /// over capacity. Samples normally arrive in order, but a binary search
+    /// keeps the buffer correct even if one arrives slightly out of order.
+    fn push(&mut self, sample: SensorData) {
+        let idx = self.samples.partition_point(|s| s.timestamp <= sample.timestamp);
+        self.samples.insert(idx, sample);
+
+        while self.samples.len() > SENSOR_HISTORY_CAPACITY {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Returns the bearing (and pitch/roll) for `timestamp`, interpolating
+    /// between the two samples that bracket it. Falls back to the nearest
+    /// sample when `timestamp` lies outside the buffer's range but within
+    /// [`LOOKUP_TOLERANCE_MS`]; otherwise returns `None`.
+    fn lookup_bearing(&self, timestamp: u64) -> Option<SensorData> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let idx = self.samples.partition_point(|s| s.timestamp <= timestamp);
+
+        if idx == 0 {
+            let first = self.samples.front().unwrap();
+            return within_tolerance(first.timestamp, timestamp).then(|| first.clone());
+        }
+        if idx == self.samples.len() {
+            let last = self.samples.back().unwrap();
+            return within_tolerance(last.timestamp, timestamp).then(|| last.clone());
+        }
+
+        let before = &self.samples[idx - 1];
+        let after = &self.samples[idx];
+        if before.timestamp == timestamp {
+            return Some(before.clone());
+        }
+
+        Some(interpolate_samples(before, after, timestamp))
+    }
+}
+
+fn within_tolerance(sample_timestamp: u64, query_timestamp: u64) -> bool {
+    sample_timestamp.abs_diff(query_timestamp) <= LOOKUP_TOLERANCE_MS
+}
+
+/// Linearly interpolates `pitch`/`roll`/`timestamp` and circularly
+/// interpolates the two headings between `before` and `after`, weighted by
+/// how far `timestamp` falls between them.
+fn interpolate_samples(before: &SensorData, after: &SensorData, timestamp: u64) -> SensorData {
+    let span = after.timestamp.saturating_sub(before.timestamp);
+    let weight = if span == 0 { 0.0 } else { (timestamp - before.timestamp) as f32 / span as f32 };
+
+    SensorData {
+        magnetic_heading: interpolate_heading(before.magnetic_heading, after.magnetic_heading, weight),
+        true_heading: interpolate_heading(before.true_heading, after.true_heading, weight),
+        heading_accuracy: before.heading_accuracy.max(after.heading_accuracy),
+        pitch: lerp(before.pitch, after.pitch, weight),
+        roll: lerp(before.roll, after.roll, weight),
+        timestamp,
+    }
+}
+
+fn lerp(a: f32, b: f32, weight: f32) -> f32 {
+    a + (b - a) * weight
+}
+
+/// Shortest-arc interpolation between two compass headings in degrees: each
+/// heading is converted to a unit vector, the vectors are lerped by
+/// `weight`, and the result is converted back via `atan2`, so wraparound at
+/// 0/360° doesn't produce a reversed (long-way-around) interpolation.
+fn interpolate_heading(a: f32, b: f32, weight: f32) -> f32 {
+    let a_rad = a.to_radians();
+    let b_rad = b.to_radians();
+    let x = lerp(a_rad.cos(), b_rad.cos(), weight);
+    let y = lerp(a_rad.sin(), b_rad.sin(), weight);
+    let heading = y.atan2(x).to_degrees();
+    (heading + 360.0) % 360.0
+}
+
+static SENSOR_HISTORY: std::sync::OnceLock<std::sync::Mutex<SensorHistory>> = std::sync::OnceLock::new();
+
+fn sensor_history() -> &'static std::sync::Mutex<SensorHistory> {
+    SENSOR_HISTORY.get_or_init(|| std::sync::Mutex::new(SensorHistory::new()))
+}
+
+/// A platform-reported heading accuracy worse than this (degrees) is treated
+/// as "low-accuracy", so `true_heading` is recomputed from
+/// `magnetic_heading` + declination instead of trusted as-is. A negative
+/// accuracy is treated as "not provided" for the same reason.
+const LOW_HEADING_ACCURACY_DEG: f32 = 15.0;
+
+/// How far the device has to move (great-circle distance, km) from the
+/// location last used to compute declination before it's recomputed; WMM
+/// declination changes slowly enough over a few tens of km that recomputing
+/// on every GPS fix would be wasted work.
+const DECLINATION_RECOMPUTE_DISTANCE_KM: f64 = 50.0;
+
+static LAST_DECLINATION_LOCATION: std::sync::OnceLock<std::sync::Mutex<Option<(f64, f64)>>> = std::sync::OnceLock::new();
+static CURRENT_DECLINATION: std::sync::OnceLock<std::sync::Mutex<f32>> = std::sync::OnceLock::new();
+
+fn last_declination_location() -> &'static std::sync::Mutex<Option<(f64, f64)>> {
+    LAST_DECLINATION_LOCATION.get_or_init(|| std::sync::Mutex::new(None))
+}
+
+fn current_declination_cell() -> &'static std::sync::Mutex<f32> {
+    CURRENT_DECLINATION.get_or_init(|| std::sync::Mutex::new(0.0))
+}
+
+fn current_declination() -> f32 {
+    current_declination_cell().lock().map(|d| *d).unwrap_or(0.0)
+}
+
+/// Great-circle distance between two lat/lon points, in km.
+fn haversine_distance_km(a: (f64, f64), b: (f64, f64)) -> f64 {
+    const EARTH_RADIUS_KM: f64 = 6371.0;
+    let (lat1, lon1) = (a.0.to_radians(), a.1.to_radians());
+    let (lat2, lon2) = (b.0.to_radians(), b.1.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = lon2 - lon1;
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+}
+
+/// Recomputes and caches the magnetic declination for `(latitude,
+/// longitude)` if the device has moved more than
+/// [`DECLINATION_RECOMPUTE_DISTANCE_KM`] since the last computation.
+fn maybe_recompute_declination(latitude: f64, longitude: f64, altitude_m: f64) {
+    let moved_enough = match *last_declination_location().lock().unwrap() {
+        Some(last) => haversine_distance_km(last, (latitude, longitude)) > DECLINATION_RECOMPUTE_DISTANCE_KM,
+        None => true,
+    };
+    if !moved_enough {
+        return;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let declination = crate::declination::magnetic_declination(latitude, longitude, altitude_m, now);
+    *current_declination_cell().lock().unwrap() = declination;
+    *last_declination_location().lock().unwrap() = Some((latitude, longitude));
+}
+
+fn normalize_heading(heading: f32) -> f32 {
+    ((heading % 360.0) + 360.0) % 360.0
+}
+
+/// Fills in `true_heading` from `magnetic_heading` + the cached declination
+/// whenever the platform-reported value is missing or low-accuracy.
+fn resolve_true_heading(sample: &mut SensorData) {
+    let usable = sample.heading_accuracy >= 0.0 && sample.heading_accuracy <= LOW_HEADING_ACCURACY_DEG;
+    if !usable {
+        sample.true_heading = normalize_heading(sample.magnetic_heading + current_declination());
+    }
+}
+
 #[derive(Default)]
 struct SensorPlugin<R: Runtime> {
     #[allow(dead_code)]
@@ -38,12 +220,17 @@ async fn start_sensor<R: Runtime>(
     {
         start_sensor_android(_app)?;
     }
-    
-    #[cfg(not(target_os = "android"))]
+
+    #[cfg(target_os = "ios")]
     {
-        return Err("Sensor API is only available on Android".to_string());
+        start_sensor_ios()?;
     }
-    
+
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        return Err("Sensor API is only available on Android and iOS".to_string());
+    }
+
     Ok(())
 }
 
@@ -56,7 +243,12 @@ async fn stop_sensor<R: Runtime>(
     {
         stop_sensor_android(_app)?;
     }
-    
+
+    #[cfg(target_os = "ios")]
+    {
+        stop_sensor_ios()?;
+    }
+
     Ok(())
 }
 
@@ -66,14 +258,48 @@ async fn update_location<R: Runtime>(
     _plugin: tauri::State<'_, SensorPlugin<R>>,
     location: LocationUpdate,
 ) -> Result<(), String> {
+    // Altitude has negligible effect on declination compared to lat/lon, and
+    // `LocationUpdate` doesn't carry one, so sea level is close enough here.
+    maybe_recompute_declination(location.latitude, location.longitude, 0.0);
+
     #[cfg(target_os = "android")]
     {
         update_location_android(_app, location.latitude, location.longitude)?;
     }
-    
+
+    #[cfg(target_os = "ios")]
+    {
+        update_location_ios(location.latitude, location.longitude)?;
+    }
+
+    Ok(())
+}
+
+/// Records a freshly-arrived sensor sample into the [`SensorHistory`] ring
+/// buffer, so later photos can resolve their bearing via `lookup_bearing`.
+#[tauri::command]
+async fn record_sensor_sample<R: Runtime>(
+    _app: tauri::AppHandle<R>,
+    _plugin: tauri::State<'_, SensorPlugin<R>>,
+    mut sample: SensorData,
+) -> Result<(), String> {
+    resolve_true_heading(&mut sample);
+    sensor_history().lock().map_err(|e| format!("Failed to lock sensor history: {}", e))?.push(sample);
     Ok(())
 }
 
+/// Looks up the (possibly interpolated) bearing for `timestamp` in the
+/// [`SensorHistory`] ring buffer.
+#[tauri::command]
+async fn lookup_bearing<R: Runtime>(
+    _app: tauri::AppHandle<R>,
+    _plugin: tauri::State<'_, SensorPlugin<R>>,
+    timestamp: u64,
+) -> Result<BearingLookupResult, String> {
+    let sample = sensor_history().lock().map_err(|e| format!("Failed to lock sensor history: {}", e))?.lookup_bearing(timestamp);
+    Ok(BearingLookupResult { found: sample.is_some(), sample })
+}
+
 #[cfg(target_os = "android")]
 fn start_sensor_android<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), String> {
     use jni::objects::{JObject, JValue};
@@ -208,24 +434,62 @@ fn update_location_android<R: Runtime>(
     Ok(())
 }
 
+// The Swift side (CMMotionManager for heading/pitch/roll, CLLocationManager
+// for `update_location`) lives in the iOS app target, outside this Rust
+// source tree, the same way `io/github/koo5/hillview/SensorService` above is
+// a Java class that isn't part of this crate either. `hillview_ios_sensor_callback`
+// is the one symbol Rust exports back, so Swift can hand samples straight to
+// `sensor_history` without a JNI-style reflective call; no Tauri event is
+// emitted for these samples.
+#[cfg(target_os = "ios")]
+extern "C" {
+    fn hillview_start_sensor();
+    fn hillview_stop_sensor();
+    fn hillview_update_location(latitude: f64, longitude: f64);
+}
+
+#[cfg(target_os = "ios")]
+fn start_sensor_ios() -> Result<(), String> {
+    unsafe { hillview_start_sensor() };
+    Ok(())
+}
+
+#[cfg(target_os = "ios")]
+fn stop_sensor_ios() -> Result<(), String> {
+    unsafe { hillview_stop_sensor() };
+    Ok(())
+}
+
+#[cfg(target_os = "ios")]
+fn update_location_ios(latitude: f64, longitude: f64) -> Result<(), String> {
+    unsafe { hillview_update_location(latitude, longitude) };
+    Ok(())
+}
+
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("sensor")
         .invoke_handler(tauri::generate_handler![
             start_sensor,
             stop_sensor,
-            update_location
+            update_location,
+            record_sensor_sample,
+            lookup_bearing
         ])
         .setup(|app, _api| {
             app.manage(SensorPlugin::<R> {
                 app_handle: Some(app.clone()),
             });
             
-            // Set up sensor data receiver on Android
+            // Set up the native sensor data receiver
             #[cfg(target_os = "android")]
             {
                 setup_sensor_receiver(app.clone());
             }
-            
+            #[cfg(target_os = "ios")]
+            {
+                setup_sensor_receiver_ios(app.clone());
+            }
+
             Ok(())
         })
         .build()
@@ -235,4 +499,31 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
 fn setup_sensor_receiver<R: Runtime>(app: tauri::AppHandle<R>) {
     // This would set up JNI callbacks to receive sensor data
     // For now, we'll emit events from the Android side
+}
+
+#[cfg(target_os = "ios")]
+fn setup_sensor_receiver_ios<R: Runtime>(_app: tauri::AppHandle<R>) {
+    // Nothing to wire up here: unlike Android's JNI bridge, the Swift side
+    // calls straight into the exported `hillview_ios_sensor_callback` below,
+    // which pushes directly into `sensor_history` without needing an
+    // `AppHandle`.
+}
+
+/// Called from Swift (CMMotionManager callback) with a JSON-encoded
+/// [`SensorData`] sample, mirroring what `record_sensor_sample` does for
+/// samples arriving over the Tauri command channel.
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn hillview_ios_sensor_callback(sample_json: *const std::os::raw::c_char) {
+    if sample_json.is_null() {
+        return;
+    }
+    let json = unsafe { std::ffi::CStr::from_ptr(sample_json) }.to_string_lossy();
+    let Ok(mut sample) = serde_json::from_str::<SensorData>(&json) else {
+        return;
+    };
+    resolve_true_heading(&mut sample);
+    if let Ok(mut history) = sensor_history().lock() {
+        history.push(sample);
+    }
 }
\ No newline at end of file
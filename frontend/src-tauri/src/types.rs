@@ -6,9 +6,73 @@ pub struct PhotoMetadata {
 	pub longitude: f64,
 	pub altitude: Option<f64>,
 	pub bearing: Option<f64>,
+	/// Ground speed in km/h (GStreamer's geo-location "speed" tag), distinct
+	/// from `bearing`: this is which way the *device* was moving, not which
+	/// way the camera was pointing.
+	pub movement_speed: Option<f64>,
+	/// True-north track angle in degrees (GStreamer's geo-location
+	/// "movement-direction" tag) the device was moving along, as opposed to
+	/// `bearing` (GPSImgDirection), which the camera faced.
+	pub movement_direction: Option<f64>,
+	/// Unix timestamp in seconds (UTC) the photo was captured at.
 	pub captured_at: i64,
 	pub accuracy: f64,
 	pub location_source: String,
 	pub bearing_source: String,
+	/// How `captured_at` was derived on read: "gps" when combined from the
+	/// GPSDateStamp/GPSTimeStamp tags, "exif_datetime" when falling back to
+	/// the camera-local DateTime tag, "unknown" if neither was present.
+	pub captured_at_source: String,
 	pub orientation_code: Option<u16>, // EXIF orientation value (1, 3, 6, 8)
 }
+
+/// Output style for [`PhotoMetadata::formatted_gps_position`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateFormat {
+	/// e.g. `51° 28' 38.1" N, 0° 0' 5.3" W`, matching what the GPS IFD
+	/// itself stores (degrees/minutes/seconds plus a hemisphere letter).
+	Dms,
+	/// e.g. `51.477250, -0.001470`.
+	DecimalDegrees,
+}
+
+impl PhotoMetadata {
+	/// Renders `latitude`/`longitude` as a human-readable position string,
+	/// the formatted-GPSPosition counterpart photo browsers usually show
+	/// alongside the raw coordinates.
+	pub fn formatted_gps_position(&self, format: CoordinateFormat) -> String {
+		match format {
+			CoordinateFormat::DecimalDegrees => format!("{:.6}, {:.6}", self.latitude, self.longitude),
+			CoordinateFormat::Dms => format!(
+				"{}, {}",
+				format_dms(self.latitude, 'N', 'S'),
+				format_dms(self.longitude, 'E', 'W'),
+			),
+		}
+	}
+
+	/// Renders `altitude` as e.g. `"10.3 m above sea level"` / `"10.3 m
+	/// below sea level"`, mirroring GPSAltitudeRef. Returns `None` when no
+	/// altitude was recorded.
+	pub fn formatted_gps_altitude(&self) -> Option<String> {
+		self.altitude.map(|altitude| {
+			if altitude >= 0.0 {
+				format!("{:.1} m above sea level", altitude)
+			} else {
+				format!("{:.1} m below sea level", altitude.abs())
+			}
+		})
+	}
+}
+
+/// Formats a signed coordinate's absolute value as `D° M' S.s" <ref>`, choosing `pos_ref`
+/// (N/E) or `neg_ref` (S/W) from the sign.
+fn format_dms(value: f64, pos_ref: char, neg_ref: char) -> String {
+	let reference = if value >= 0.0 { pos_ref } else { neg_ref };
+	let abs = value.abs();
+	let degrees = abs.floor() as u32;
+	let minutes_f = (abs - degrees as f64) * 60.0;
+	let minutes = minutes_f.floor() as u32;
+	let seconds = (minutes_f - minutes as f64) * 60.0;
+	format!("{}\u{b0} {}' {:.1}\" {}", degrees, minutes, seconds, reference)
+}
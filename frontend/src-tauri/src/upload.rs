@@ -0,0 +1,272 @@
+//! Pluggable upload backend for pushing processed photos straight to
+//! object storage from the Tauri core, as an alternative (or addition) to
+//! the Android plugin's `uploadPhoto`/`retry_failed_uploads` worker. This is
+//! what lets desktop, which has no Kotlin worker, upload photos at all.
+
+use hmac::{Hmac, Mac};
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use tauri_plugin_hillview::{HillviewExt, S3UploadConfig, UploadCompleteEvent, UploadFailedEvent, UploadProgressEvent};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// A destination photos can be uploaded to. Implemented by [`S3Backend`],
+/// which is the backend `upload_processed_photo` constructs and uses.
+#[async_trait::async_trait]
+pub trait UploadBackend: Send + Sync {
+	/// Uploads `data` under `key`, replacing any object already there. The
+	/// content-addressed dedup check is a separate [`UploadBackend::exists`] call.
+	async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), String>;
+
+	/// Returns whether an object already exists at `key`.
+	async fn exists(&self, key: &str) -> Result<bool, String>;
+}
+
+/// Builds the content-addressed object key used for dedup: the upload is
+/// skipped if this key already exists, since the content hash guarantees
+/// the bytes are identical.
+pub fn dedup_key(file_hash: &str, extension: &str) -> String {
+	let prefix = &file_hash[..file_hash.len().min(4)];
+	let (a, b) = prefix.split_at(prefix.len().min(2));
+	format!("{}/{}/{}.{}", a, b, file_hash, extension)
+}
+
+/// Per-photo upload progress of the S3 path, keyed by dedup key and
+/// summarised by [`s3_upload_counts`]. `get_upload_status` does not read it.
+#[derive(Debug, Clone, Default)]
+pub struct UploadProgress {
+	pub bytes_transferred: u64,
+	pub bytes_total: u64,
+	pub failed: bool,
+}
+
+static UPLOAD_PROGRESS: OnceLock<Mutex<HashMap<String, UploadProgress>>> = OnceLock::new();
+
+fn progress_map() -> &'static Mutex<HashMap<String, UploadProgress>> {
+	UPLOAD_PROGRESS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Counts of in-flight/failed uploads tracked by the S3 backend, as
+/// `(pending, failed)`. `get_upload_status` does not include these counts.
+pub fn s3_upload_counts() -> (i32, i32) {
+	let progress = match progress_map().lock() {
+		Ok(guard) => guard,
+		Err(_) => return (0, 0),
+	};
+
+	let pending = progress
+		.values()
+		.filter(|p| !p.failed && p.bytes_transferred < p.bytes_total)
+		.count() as i32;
+	let failed = progress.values().filter(|p| p.failed).count() as i32;
+	(pending, failed)
+}
+
+fn set_progress(photo_id: &str, progress: UploadProgress) {
+	if let Ok(mut map) = progress_map().lock() {
+		map.insert(photo_id.to_string(), progress);
+	}
+}
+
+/// S3-compatible backend (AWS S3, MinIO, etc.), signed with a minimal
+/// hand-rolled AWS SigV4 implementation so we don't need the full AWS SDK
+/// just to PUT a handful of JPEGs.
+pub struct S3Backend {
+	config: S3UploadConfig,
+	client: reqwest::Client,
+}
+
+impl S3Backend {
+	pub fn new(config: S3UploadConfig) -> Self {
+		Self {
+			config,
+			client: reqwest::Client::new(),
+		}
+	}
+
+	fn endpoint_base(&self) -> String {
+		self.config
+			.endpoint
+			.clone()
+			.unwrap_or_else(|| format!("https://{}.s3.{}.amazonaws.com", self.config.bucket, self.config.region))
+	}
+
+	fn object_url(&self, key: &str) -> String {
+		format!("{}/{}", self.endpoint_base().trim_end_matches('/'), key)
+	}
+
+	/// Computes the `Authorization` header for a single SigV4-signed
+	/// request, per the AWS "signing a request" algorithm.
+	fn sign(&self, method: &str, key: &str, payload: &[u8], amz_date: &str, date_stamp: &str) -> String {
+		let payload_hash = hex::encode(Sha256::digest(payload));
+		let host = self
+			.config
+			.endpoint
+			.as_deref()
+			.map(|e| e.trim_start_matches("https://").trim_start_matches("http://").to_string())
+			.unwrap_or_else(|| format!("{}.s3.{}.amazonaws.com", self.config.bucket, self.config.region));
+
+		let canonical_headers = format!("host:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n", host, payload_hash, amz_date);
+		let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+		let canonical_request = format!(
+			"{}\n/{}\n\n{}\n{}\n{}",
+			method, key, canonical_headers, signed_headers, payload_hash
+		);
+
+		let credential_scope = format!("{}/{}/s3/aws4_request", date_stamp, self.config.region);
+		let string_to_sign = format!(
+			"AWS4-HMAC-SHA256\n{}\n{}\n{}",
+			amz_date,
+			credential_scope,
+			hex::encode(Sha256::digest(canonical_request.as_bytes()))
+		);
+
+		let signing_key = self.derive_signing_key(date_stamp);
+		let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+		format!(
+			"AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+			self.config.access_key_id, credential_scope, signed_headers, signature
+		)
+	}
+
+	fn derive_signing_key(&self, date_stamp: &str) -> Vec<u8> {
+		let k_secret = format!("AWS4{}", self.config.secret_access_key);
+		let k_date = hmac_sha256(k_secret.as_bytes(), date_stamp.as_bytes());
+		let k_region = hmac_sha256(&k_date, self.config.region.as_bytes());
+		let k_service = hmac_sha256(&k_region, b"s3");
+		hmac_sha256(&k_service, b"aws4_request")
+	}
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+	let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+	mac.update(data);
+	mac.finalize().into_bytes().to_vec()
+}
+
+#[async_trait::async_trait]
+impl UploadBackend for S3Backend {
+	async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), String> {
+		let now = chrono::Utc::now();
+		let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+		let date_stamp = now.format("%Y%m%d").to_string();
+		let payload_hash = hex::encode(Sha256::digest(&data));
+		let authorization = self.sign("PUT", key, &data, &amz_date, &date_stamp);
+
+		let total = data.len() as u64;
+		set_progress(key, UploadProgress { bytes_transferred: 0, bytes_total: total, failed: false });
+
+		let response = self
+			.client
+			.put(self.object_url(key))
+			.header("x-amz-date", amz_date)
+			.header("x-amz-content-sha256", payload_hash)
+			.header("Authorization", authorization)
+			.header("Content-Type", content_type)
+			.body(data)
+			.send()
+			.await
+			.map_err(|e| format!("S3 upload request failed: {}", e))?;
+
+		if response.status().is_success() {
+			set_progress(key, UploadProgress { bytes_transferred: total, bytes_total: total, failed: false });
+			info!("🢄☁️ Uploaded {} to S3 backend ({} bytes)", key, total);
+			Ok(())
+		} else {
+			set_progress(key, UploadProgress { bytes_transferred: 0, bytes_total: total, failed: true });
+			let status = response.status();
+			let body = response.text().await.unwrap_or_default();
+			warn!("🢄☁️ S3 upload failed for {}: {} {}", key, status, body);
+			Err(format!("S3 upload failed: {} {}", status, body))
+		}
+	}
+
+	async fn exists(&self, key: &str) -> Result<bool, String> {
+		let now = chrono::Utc::now();
+		let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+		let date_stamp = now.format("%Y%m%d").to_string();
+		let authorization = self.sign("HEAD", key, b"", &amz_date, &date_stamp);
+
+		let response = self
+			.client
+			.head(self.object_url(key))
+			.header("x-amz-date", &amz_date)
+			.header("x-amz-content-sha256", hex::encode(Sha256::digest(b"")))
+			.header("Authorization", authorization)
+			.send()
+			.await
+			.map_err(|e| format!("S3 HEAD request failed: {}", e))?;
+
+		Ok(response.status().is_success())
+	}
+}
+
+static S3_CONFIG: OnceLock<Mutex<Option<S3UploadConfig>>> = OnceLock::new();
+
+fn s3_config_slot() -> &'static Mutex<Option<S3UploadConfig>> {
+	S3_CONFIG.get_or_init(|| Mutex::new(None))
+}
+
+/// Configures (or clears, via `None`) the Rust-side S3 upload backend.
+#[tauri::command]
+pub fn set_s3_upload_config(config: Option<S3UploadConfig>) -> Result<(), String> {
+	*s3_config_slot().lock().map_err(|e| format!("Failed to lock S3 config: {}", e))? = config;
+	Ok(())
+}
+
+/// Returns the currently configured S3 backend settings, if any.
+pub fn s3_upload_config() -> Option<S3UploadConfig> {
+	s3_config_slot().lock().ok().and_then(|guard| guard.clone())
+}
+
+/// Uploads a processed photo to the configured S3 backend, skipping the
+/// transfer entirely if an object already exists under the dedup key (the
+/// md5 content hash computed during save). Emits `hillview://upload-*`
+/// events off `app_handle` so the UI can render progress without polling
+/// `get_upload_status`.
+pub async fn upload_processed_photo(
+	app_handle: &tauri::AppHandle,
+	config: &S3UploadConfig,
+	file_hash: &str,
+	data: Vec<u8>,
+) -> Result<(), String> {
+	let backend = S3Backend::new(config.clone());
+	let key = dedup_key(file_hash, "jpg");
+	let bytes_total = data.len() as u64;
+
+	if backend.exists(&key).await? {
+		info!("🢄☁️ Skipping S3 upload for {}, object already exists", key);
+		let _ = app_handle
+			.hillview()
+			.emit_upload_complete(UploadCompleteEvent { photo_id: key, bytes_total });
+		return Ok(());
+	}
+
+	let _ = app_handle.hillview().emit_upload_progress(UploadProgressEvent {
+		photo_id: key.clone(),
+		bytes_transferred: 0,
+		bytes_total,
+		retry_count: 0,
+	});
+
+	match backend.upload(&key, data, "image/jpeg").await {
+		Ok(()) => {
+			let _ = app_handle
+				.hillview()
+				.emit_upload_complete(UploadCompleteEvent { photo_id: key, bytes_total });
+			Ok(())
+		}
+		Err(e) => {
+			let _ = app_handle.hillview().emit_upload_failed(UploadFailedEvent {
+				photo_id: key,
+				retry_count: 0,
+				error: e.clone(),
+			});
+			Err(e)
+		}
+	}
+}
@@ -0,0 +1,133 @@
+//! Serializes geodata and provenance as a standard XMP (RDF/XML) packet, as
+//! an interoperable alternative to the non-standard JSON blob this crate
+//! stuffs into the EXIF UserComment (see `photo_exif::ExifBuilder::add_provenance`).
+//! Mainstream metadata viewers and editors understand `exif:GPS*`/`tiff:Orientation`
+//! XMP properties; the custom `hillview:` namespace carries the provenance
+//! fields the UserComment JSON was invented for, so both can coexist.
+
+use crate::types::PhotoMetadata;
+use log::warn;
+
+/// XMP packets larger than this are truncated with a warning, mirroring the
+/// UserComment size cap in `photo_exif::ExifBuilder::add_provenance`.
+const MAX_XMP_PACKET_SIZE: usize = 65000;
+
+const ADOBE_XMP_NAMESPACE: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// Formats a signed decimal degree value as the XMP GPS `deg,min.decmin[NSEW]`
+/// form (e.g. `37,46.500000N`), per the XMP specification's GPS coordinate
+/// encoding (the same "ref" convention `ExifBuilder::add_gps_data` uses).
+fn format_xmp_gps_coordinate(value: f64, positive_ref: char, negative_ref: char) -> String {
+    let reference = if value >= 0.0 { positive_ref } else { negative_ref };
+    let abs = value.abs();
+    let degrees = abs.floor() as u32;
+    let minutes = (abs - degrees as f64) * 60.0;
+    format!("{},{:.6}{}", degrees, minutes, reference)
+}
+
+/// Escapes the characters that are unsafe in an XML attribute value.
+fn escape_xml_attr(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Builds a complete `<?xpacket ...?>`-wrapped RDF/XML packet carrying
+/// `exif:GPSLatitude`/`exif:GPSLongitude`/`exif:GPSImgDirection`,
+/// `exif:DateTimeOriginal`, `tiff:Orientation`, and the provenance fields
+/// under a custom `hillview:` namespace.
+pub fn build_xmp_packet(metadata: &PhotoMetadata) -> String {
+    let latitude = format_xmp_gps_coordinate(metadata.latitude, 'N', 'S');
+    let longitude = format_xmp_gps_coordinate(metadata.longitude, 'E', 'W');
+
+    let datetime_original = chrono::DateTime::from_timestamp(metadata.captured_at, 0)
+        .unwrap_or_else(chrono::Utc::now)
+        .format("%Y-%m-%dT%H:%M:%SZ")
+        .to_string();
+
+    let mut attributes = format!(
+        concat!(
+            "exif:GPSLatitude=\"{}\"\n",
+            "    exif:GPSLongitude=\"{}\"\n",
+            "    exif:DateTimeOriginal=\"{}\"\n",
+            "    hillview:locationSource=\"{}\"\n",
+            "    hillview:bearingSource=\"{}\"",
+        ),
+        latitude,
+        longitude,
+        datetime_original,
+        escape_xml_attr(&metadata.location_source),
+        escape_xml_attr(&metadata.bearing_source),
+    );
+
+    if let Some(bearing) = metadata.bearing {
+        attributes.push_str(&format!("\n    exif:GPSImgDirection=\"{:.4}\"", bearing));
+    }
+    if let Some(orientation) = metadata.orientation_code {
+        attributes.push_str(&format!("\n    tiff:Orientation=\"{}\"", orientation));
+    }
+
+    format!(
+        concat!(
+            "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n",
+            "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n",
+            " <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n",
+            "  <rdf:Description rdf:about=\"\"\n",
+            "    xmlns:exif=\"http://ns.adobe.com/exif/1.0/\"\n",
+            "    xmlns:tiff=\"http://ns.adobe.com/tiff/1.0/\"\n",
+            "    xmlns:hillview=\"https://github.com/koo5/hillview/ns/1.0/\"\n",
+            "    {}/>\n",
+            " </rdf:RDF>\n",
+            "</x:xmpmeta>\n",
+            "<?xpacket end=\"w\"?>",
+        ),
+        attributes
+    )
+}
+
+/// Wraps `packet` in an `APP1`/`http://ns.adobe.com/xap/1.0/` JPEG segment,
+/// ready to splice into a JPEG byte stream right after the SOI marker.
+/// Truncates (with a warning) packets too large for a JPEG segment's
+/// 2-byte length field.
+pub fn app1_segment(packet: &str) -> Vec<u8> {
+    let mut payload = packet.as_bytes();
+    if payload.len() > MAX_XMP_PACKET_SIZE {
+        warn!("XMP packet too long ({} bytes), truncating", payload.len());
+        payload = &payload[..MAX_XMP_PACKET_SIZE];
+    }
+
+    let length = (2 + ADOBE_XMP_NAMESPACE.len() + payload.len()) as u16;
+
+    let mut segment = Vec::with_capacity(4 + ADOBE_XMP_NAMESPACE.len() + payload.len());
+    segment.extend_from_slice(&[0xFF, 0xE1]);
+    segment.extend_from_slice(&length.to_be_bytes());
+    segment.extend_from_slice(ADOBE_XMP_NAMESPACE);
+    segment.extend_from_slice(payload);
+    segment
+}
+
+/// Splices an XMP `APP1` segment into `jpeg_bytes` immediately after the
+/// SOI marker. Does not look for or replace a pre-existing XMP segment.
+pub fn insert_xmp_segment(jpeg_bytes: &[u8], packet: &str) -> Result<Vec<u8>, String> {
+    if jpeg_bytes.len() < 2 || jpeg_bytes[0..2] != [0xFF, 0xD8] {
+        return Err("Not a valid JPEG (missing SOI marker)".to_string());
+    }
+
+    let segment = app1_segment(packet);
+    let mut output = Vec::with_capacity(jpeg_bytes.len() + segment.len());
+    output.extend_from_slice(&jpeg_bytes[0..2]);
+    output.extend_from_slice(&segment);
+    output.extend_from_slice(&jpeg_bytes[2..]);
+    Ok(output)
+}
+
+/// Writes `packet` as a `.xmp` sidecar next to `photo_path`, the form some
+/// tools (e.g. RAW workflows) prefer over an embedded segment.
+pub fn write_xmp_sidecar(photo_path: &std::path::Path, packet: &str) -> Result<(), String> {
+    let sidecar_path = photo_path.with_extension("xmp");
+    std::fs::write(&sidecar_path, packet)
+        .map_err(|e| format!("Failed to write XMP sidecar: {}", e))
+}
@@ -1,4 +1,33 @@
-const COMMANDS: &[&str] = &["start_sensor", "stop_sensor", "start_precise_location_listener", "stop_precise_location_listener", "register_listener"];
+const COMMANDS: &[&str] = &[
+  "ping",
+  "start_sensor",
+  "stop_sensor",
+  "update_sensor_location",
+  "start_precise_location_listener",
+  "stop_precise_location_listener",
+  "set_auto_upload_enabled",
+  "get_upload_status",
+  "set_upload_config",
+  "retry_failed_uploads",
+  "store_auth_token",
+  "get_auth_token",
+  "clear_auth_token",
+  "register_client_public_key",
+  "get_device_photos",
+  "refresh_photo_scan",
+  "import_photos",
+  "add_photo_to_database",
+  "share_photo",
+  "photo_worker_process",
+  "get_push_distributors",
+  "get_push_registration_status",
+  "select_push_distributor",
+  "get_notification_settings",
+  "set_notification_settings",
+  "check_tauri_permissions",
+  "request_post_notification_permission",
+  "test_show_notification",
+];
 fn main() {
   tauri_plugin::Builder::new(COMMANDS)
     .android_path("android")
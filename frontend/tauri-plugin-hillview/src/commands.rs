@@ -76,50 +76,25 @@ pub(crate) async fn set_auto_upload_enabled<R: Runtime>(
 
 #[command(rename_all = "snake_case")]
 pub(crate) async fn get_upload_status<R: Runtime>(
-    _app: AppHandle<R>,
+    app: AppHandle<R>,
 ) -> Result<UploadStatusResponse> {
-    #[cfg(mobile)]
-    {
-        return _app.hillview().get_upload_status();
-    }
-
-    #[cfg(desktop)]
-    {
-        return Err(crate::Error::from("Upload status is only available on mobile devices"));
-    }
+    app.hillview().get_upload_status()
 }
 
 #[command(rename_all = "snake_case")]
-#[allow(unused_variables)]
 pub(crate) async fn set_upload_config<R: Runtime>(
     app: AppHandle<R>,
     config: UploadConfig,
 ) -> Result<BasicResponse> {
-    #[cfg(mobile)]
-    {
-        return app.hillview().set_upload_config(config);
-    }
-
-    #[cfg(desktop)]
-    {
-        return Err(crate::Error::from("Upload config is only available on mobile devices"));
-    }
+    app.hillview().set_upload_config(config)
 }
 
 
 #[command(rename_all = "snake_case")]
 pub(crate) async fn retry_failed_uploads<R: Runtime>(
-    _app: AppHandle<R>,
+    app: AppHandle<R>,
 ) -> Result<BasicResponse> {
-    #[cfg(mobile)]
-    {
-        return _app.hillview().retry_failed_uploads();
-    }
-
-    #[cfg(desktop)]
-    {
-        return Err(crate::Error::from("Retry uploads is only available on mobile devices"));
-    }
+    app.hillview().retry_failed_uploads()
 }
 
 #[command(rename_all = "snake_case")]
@@ -223,47 +198,23 @@ pub(crate) async fn clear_auth_token<R: Runtime>(
 
 #[command(rename_all = "snake_case")]
 pub(crate) async fn get_device_photos<R: Runtime>(
-    #[allow(unused_variables)] app: AppHandle<R>,
+    app: AppHandle<R>,
 ) -> Result<crate::models::DevicePhotosResponse> {
-    #[cfg(mobile)]
-    {
-        return app.hillview().get_device_photos();
-    }
-
-    #[cfg(desktop)]
-    {
-        return Err(crate::Error::from("Device photos are only available on mobile devices"));
-    }
+    app.hillview().get_device_photos()
 }
 
 #[command(rename_all = "snake_case")]
 pub(crate) async fn refresh_photo_scan<R: Runtime>(
-    #[allow(unused_variables)] app: AppHandle<R>,
+    app: AppHandle<R>,
 ) -> Result<crate::models::PhotoScanResponse> {
-    #[cfg(mobile)]
-    {
-        return app.hillview().refresh_photo_scan();
-    }
-
-    #[cfg(desktop)]
-    {
-        return Err(crate::Error::from("Photo scanning is only available on mobile devices"));
-    }
+    app.hillview().refresh_photo_scan()
 }
 
 #[command(rename_all = "snake_case")]
 pub(crate) async fn import_photos<R: Runtime>(
-    #[allow(unused_variables)] app: AppHandle<R>,
+    app: AppHandle<R>,
 ) -> Result<crate::models::FileImportResponse> {
-    #[cfg(mobile)]
-    {
-        return app.hillview().import_photos();
-    }
-
-    #[cfg(desktop)]
-    {
-        return Err(crate::Error::from("Photo import is only available on mobile devices"));
-    }
+    app.hillview().import_photos()
 }
 
 #[command(rename_all = "snake_case")]
@@ -365,7 +316,6 @@ pub(crate) async fn select_push_distributor<R: Runtime>(
 // Notification Commands
 
 
-#[cfg(mobile)]
 #[command(rename_all = "snake_case")]
 pub(crate) async fn get_notification_settings<R: Runtime>(
     app: AppHandle<R>,
@@ -373,7 +323,6 @@ pub(crate) async fn get_notification_settings<R: Runtime>(
     app.hillview().get_notification_settings()
 }
 
-#[cfg(mobile)]
 #[command(rename_all = "snake_case")]
 pub(crate) async fn set_notification_settings<R: Runtime>(
     app: AppHandle<R>,
@@ -384,7 +333,6 @@ pub(crate) async fn set_notification_settings<R: Runtime>(
 
 // Tauri permission system commands
 
-#[cfg(mobile)]
 #[command(rename_all = "snake_case")]
 pub(crate) async fn check_tauri_permissions<R: Runtime>(
     app: AppHandle<R>,
@@ -396,7 +344,6 @@ pub(crate) async fn check_tauri_permissions<R: Runtime>(
     })
 }
 
-#[cfg(mobile)]
 #[command(rename_all = "snake_case")]
 pub(crate) async fn request_post_notification_permission<R: Runtime>(
     app: AppHandle<R>,
@@ -408,7 +355,6 @@ pub(crate) async fn request_post_notification_permission<R: Runtime>(
     Ok(format!("{:?}", permission_state))
 }
 
-#[cfg(mobile)]
 #[command(rename_all = "snake_case")]
 pub(crate) async fn test_show_notification<R: Runtime>(
     app: AppHandle<R>,
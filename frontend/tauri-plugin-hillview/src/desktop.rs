@@ -1,19 +1,87 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::warn;
 use serde::de::DeserializeOwned;
-use tauri::{plugin::PluginApi, AppHandle, Runtime};
+use tauri::{
+  async_runtime,
+  plugin::{PermissionState, PluginApi},
+  AppHandle, Emitter, Runtime,
+};
 
 use crate::models::*;
+use crate::shared_types::{
+  events, DevicePhotoMetadata, PhotoMetadata, ScanProgressEvent, UploadCompleteEvent,
+  UploadFailedEvent, UploadProgressEvent,
+};
+use crate::HillviewExt;
+
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png"];
 
 pub fn init<R: Runtime, C: DeserializeOwned>(
   app: &AppHandle<R>,
   _api: PluginApi<R, C>,
 ) -> crate::Result<Hillview<R>> {
-  Ok(Hillview(app.clone()))
+  let persisted = crate::persistence::load_queue(app);
+  let failed_uploads = persisted.iter().map(|photo| photo.id.clone()).collect();
+
+  Ok(Hillview {
+    app: app.clone(),
+    state: Mutex::new(DesktopState {
+      scan_dirs: default_scan_dirs(),
+      photos: persisted,
+      failed_uploads,
+      ..Default::default()
+    }),
+    tasks: Mutex::new(Vec::new()),
+  })
 }
 
-/// Access to the hillview APIs.
-pub struct Hillview<R: Runtime>(AppHandle<R>);
+/// Defaults to the user's Pictures directory. There's no directory-picker
+/// command yet, so this is the only source of `scan_dirs` for now.
+fn default_scan_dirs() -> Vec<PathBuf> {
+  std::env::var("HOME")
+    .map(|home| vec![PathBuf::from(home).join("Pictures")])
+    .unwrap_or_default()
+}
+
+struct DesktopState {
+  scan_dirs: Vec<PathBuf>,
+  photos: Vec<DevicePhotoMetadata>,
+  upload_config: Option<UploadConfig>,
+  failed_uploads: Vec<String>,
+  notifications_enabled: bool,
+}
+
+impl Default for DesktopState {
+  fn default() -> Self {
+    Self {
+      scan_dirs: Vec::new(),
+      photos: Vec::new(),
+      upload_config: None,
+      failed_uploads: Vec::new(),
+      notifications_enabled: true,
+    }
+  }
+}
+
+/// Access to the hillview APIs. Desktop has no Kotlin/Swift worker behind
+/// it, so this implements photo scanning and uploads itself against the
+/// local filesystem rather than delegating to a native plugin.
+pub struct Hillview<R: Runtime> {
+  app: AppHandle<R>,
+  state: Mutex<DesktopState>,
+  /// Handles for in-flight `retry_failed_uploads` tasks, so they can be
+  /// aborted cleanly on app exit instead of being silently dropped.
+  tasks: Mutex<Vec<async_runtime::JoinHandle<()>>>,
+}
 
 impl<R: Runtime> Hillview<R> {
+  fn lock_state(&self) -> crate::Result<std::sync::MutexGuard<'_, DesktopState>> {
+    self.state.lock().map_err(|_| crate::Error::from("Desktop photo state poisoned"))
+  }
+
   pub fn ping(&self, payload: PingRequest) -> crate::Result<PingResponse> {
     Ok(PingResponse {
       value: payload.value,
@@ -37,4 +105,453 @@ impl<R: Runtime> Hillview<R> {
   pub fn select_push_distributor(&self, _package_name: String) -> crate::Result<BasicResponse> {
     Err(crate::Error::from("Push notifications are only available on mobile devices"))
   }
+
+  // Photo scanning/import, walking `scan_dirs` on the local filesystem.
+
+  pub fn get_device_photos(&self) -> crate::Result<DevicePhotosResponse> {
+    let state = self.lock_state()?;
+    let photos = state
+      .photos
+      .iter()
+      .map(|photo| serde_json::to_value(photo).unwrap_or(serde_json::Value::Null))
+      .collect();
+    Ok(DevicePhotosResponse {
+      photos,
+      last_updated: chrono::Utc::now().timestamp(),
+    })
+  }
+
+  pub fn refresh_photo_scan(&self) -> crate::Result<PhotoScanResponse> {
+    let scan_dirs = self.lock_state()?.scan_dirs.clone();
+    let (found, scan_errors) = scan_directories(&scan_dirs);
+    let photos_added = found.len() as i32;
+    self.lock_state()?.photos = found;
+
+    let _ = self.app.emit(
+      events::SCAN_PROGRESS,
+      ScanProgressEvent {
+        photos_scanned: photos_added as u32,
+        photos_total: Some(photos_added as u32),
+      },
+    );
+
+    Ok(PhotoScanResponse {
+      photos_added,
+      scan_errors,
+      success: true,
+      error: None,
+    })
+  }
+
+  pub fn import_photos(&self) -> crate::Result<FileImportResponse> {
+    // There's no `tauri-plugin-dialog` dependency for a native file picker
+    // yet, so "import" re-scans `scan_dirs` and treats everything found
+    // there as selected.
+    let scan = self.refresh_photo_scan()?;
+    let selected_files = self.lock_state()?.photos.iter().map(|photo| photo.path.clone()).collect();
+
+    Ok(FileImportResponse {
+      success: scan.success,
+      selected_files,
+      imported_count: scan.photos_added,
+      failed_count: Some(scan.scan_errors),
+      failed_files: None,
+      import_errors: None,
+      scan_result: None,
+      error: scan.error,
+    })
+  }
+
+  // Upload queue, pushing to `upload_config.server_url` in the background.
+
+  pub fn set_upload_config(&self, config: UploadConfig) -> crate::Result<BasicResponse> {
+    self.lock_state()?.upload_config = Some(config);
+    Ok(BasicResponse {
+      success: true,
+      error: None,
+    })
+  }
+
+  pub fn get_upload_status(&self) -> crate::Result<UploadStatusResponse> {
+    let state = self.lock_state()?;
+    Ok(UploadStatusResponse {
+      auto_upload_enabled: state.upload_config.is_some(),
+      auto_upload_prompt_enabled: false,
+      pending_uploads: 0,
+      failed_uploads: state.failed_uploads.len() as i32,
+      s3_pending_uploads: 0,
+      s3_failed_uploads: 0,
+      error: None,
+    })
+  }
+
+  pub fn retry_failed_uploads(&self) -> crate::Result<BasicResponse> {
+    let (server_url, failed, photos) = {
+      let state = self.lock_state()?;
+      (state.upload_config.as_ref().and_then(|c| c.server_url.clone()), state.failed_uploads.clone(), state.photos.clone())
+    };
+
+    let Some(server_url) = server_url else {
+      return Ok(BasicResponse { success: true, error: None });
+    };
+
+    // Runs off the command thread: each retry reads the file and does
+    // network I/O, neither of which should hold up the `invoke` response.
+    // `failed_uploads` is left untouched until an attempt actually
+    // succeeds, rather than cleared up front, so a photo whose retry is
+    // still in flight (or never reached) when the app exits and aborts
+    // this task stays queued and gets persisted, instead of silently
+    // vanishing.
+    let app = self.app.clone();
+    let handle = async_runtime::spawn(async move {
+      let client = reqwest::Client::new();
+      for photo_id in failed {
+        let Some(photo) = photos.iter().find(|p| p.id == photo_id) else { continue };
+        if upload_one(&client, &app, &server_url, photo).await {
+          let _ = app.hillview().clear_failed_upload(&photo_id);
+        }
+      }
+    });
+    if let Ok(mut tasks) = self.tasks.lock() {
+      tasks.retain(|task| !task.is_finished());
+      tasks.push(handle);
+    }
+
+    Ok(BasicResponse {
+      success: true,
+      error: None,
+    })
+  }
+
+  /// Aborts any `retry_failed_uploads` tasks still running, called on app
+  /// exit so a half-finished upload doesn't race the process going away.
+  pub fn cancel_pending_uploads(&self) {
+    if let Ok(mut tasks) = self.tasks.lock() {
+      for task in tasks.drain(..) {
+        task.abort();
+      }
+    }
+  }
+
+  /// Removes `photo_id` from the failed-upload queue once `retry_failed_uploads`
+  /// confirms it actually succeeded.
+  pub fn clear_failed_upload(&self, photo_id: &str) -> crate::Result<()> {
+    self.lock_state()?.failed_uploads.retain(|id| id != photo_id);
+    Ok(())
+  }
+
+  /// Persists the current failed-upload queue to disk so it can be reloaded
+  /// (see `init`) and retried after the app restarts.
+  pub fn persist_upload_queue(&self) -> crate::Result<()> {
+    let state = self.lock_state()?;
+    let failed: Vec<_> = state
+      .photos
+      .iter()
+      .filter(|photo| state.failed_uploads.contains(&photo.id))
+      .cloned()
+      .collect();
+    crate::persistence::save_queue(&self.app, &failed)
+  }
+
+  // Notifications. There's no native permission prompt on desktop, so the
+  // permission state is always `Granted`; `notifications_enabled` (toggled
+  // by `set_notification_settings`) is the only thing that can suppress one.
+
+  pub fn get_notification_settings(&self) -> crate::Result<NotificationSettingsResponse> {
+    Ok(NotificationSettingsResponse {
+      enabled: self.lock_state()?.notifications_enabled,
+      success: true,
+      error: None,
+    })
+  }
+
+  pub fn set_notification_settings(&self, enabled: bool) -> crate::Result<BasicResponse> {
+    self.lock_state()?.notifications_enabled = enabled;
+    Ok(BasicResponse {
+      success: true,
+      error: None,
+    })
+  }
+
+  pub fn check_tauri_permissions(&self) -> crate::Result<TauriPermissionResponse> {
+    Ok(TauriPermissionResponse {
+      post_notification: PermissionState::Granted,
+    })
+  }
+
+  pub fn request_post_notification_permission(&self) -> crate::Result<PermissionState> {
+    Ok(PermissionState::Granted)
+  }
+
+  pub fn test_show_notification(&self, title: String, message: String) -> crate::Result<BasicResponse> {
+    self.show_notification(NotificationOptions {
+      title,
+      body: message,
+      icon: None,
+    })
+  }
+
+  /// Surfaces a completed/failed upload as a desktop notification, the
+  /// counterpart to the `hillview://upload-complete`/`upload-failed` events
+  /// for users who aren't watching the app window.
+  pub fn show_upload_notification(&self, photo_id: &str, succeeded: bool, detail: Option<&str>) -> crate::Result<BasicResponse> {
+    let title = if succeeded { "Upload complete" } else { "Upload failed" }.to_string();
+    let body = match detail {
+      Some(detail) => format!("{}: {}", photo_id, detail),
+      None => photo_id.to_string(),
+    };
+    self.show_notification(NotificationOptions { title, body, icon: None })
+  }
+
+  fn show_notification(&self, options: NotificationOptions) -> crate::Result<BasicResponse> {
+    if !self.lock_state()?.notifications_enabled {
+      return Ok(BasicResponse {
+        success: false,
+        error: Some("Notifications are disabled".to_string()),
+      });
+    }
+
+    let mut notification = notify_rust::Notification::new();
+    notification.summary(&options.title).body(&options.body);
+    if let Some(icon) = &options.icon {
+      notification.icon(icon);
+    }
+
+    match notification.show() {
+      Ok(_) => Ok(BasicResponse { success: true, error: None }),
+      Err(e) => Ok(BasicResponse {
+        success: false,
+        error: Some(e.to_string()),
+      }),
+    }
+  }
+
+  // Progress events, so the frontend can `listen()` instead of polling
+  // `get_upload_status`/`refresh_photo_scan`.
+
+  pub fn emit_upload_progress(&self, event: UploadProgressEvent) -> crate::Result<()> {
+    self.app.emit(events::UPLOAD_PROGRESS, event).map_err(Into::into)
+  }
+
+  pub fn emit_upload_complete(&self, event: UploadCompleteEvent) -> crate::Result<()> {
+    self.app.emit(events::UPLOAD_COMPLETE, event).map_err(Into::into)
+  }
+
+  pub fn emit_upload_failed(&self, event: UploadFailedEvent) -> crate::Result<()> {
+    self.app.emit(events::UPLOAD_FAILED, event).map_err(Into::into)
+  }
+
+  pub fn emit_scan_progress(&self, event: ScanProgressEvent) -> crate::Result<()> {
+    self.app.emit(events::SCAN_PROGRESS, event).map_err(Into::into)
+  }
+}
+
+/// Uploads a single queued photo to `server_url`, emitting progress/result
+/// events (this runs detached, off the `retry_failed_uploads` response).
+/// Returns whether the upload succeeded, so the caller can re-queue it
+/// otherwise.
+async fn upload_one<R: Runtime>(client: &reqwest::Client, app: &AppHandle<R>, server_url: &str, photo: &DevicePhotoMetadata) -> bool {
+  let data = match fs::read(&photo.path) {
+    Ok(data) => data,
+    Err(e) => {
+      let _ = app.emit(
+        events::UPLOAD_FAILED,
+        UploadFailedEvent {
+          photo_id: photo.id.clone(),
+          retry_count: 1,
+          error: e.to_string(),
+        },
+      );
+      return false;
+    }
+  };
+  let bytes_total = data.len() as u64;
+  let _ = app.emit(
+    events::UPLOAD_PROGRESS,
+    UploadProgressEvent {
+      photo_id: photo.id.clone(),
+      bytes_transferred: 0,
+      bytes_total,
+      retry_count: 1,
+    },
+  );
+
+  let url = format!("{}/{}", server_url.trim_end_matches('/'), photo.filename);
+  let result = client.put(url).body(data).send().await;
+  match result {
+    Ok(response) if response.status().is_success() => {
+      let _ = app.emit(events::UPLOAD_COMPLETE, UploadCompleteEvent { photo_id: photo.id.clone(), bytes_total });
+      let _ = app.hillview().show_upload_notification(&photo.id, true, None);
+      true
+    }
+    Ok(response) => {
+      let error = format!("server returned {}", response.status());
+      let _ = app.emit(
+        events::UPLOAD_FAILED,
+        UploadFailedEvent {
+          photo_id: photo.id.clone(),
+          retry_count: 1,
+          error: error.clone(),
+        },
+      );
+      let _ = app.hillview().show_upload_notification(&photo.id, false, Some(&error));
+      false
+    }
+    Err(e) => {
+      let error = e.to_string();
+      let _ = app.emit(
+        events::UPLOAD_FAILED,
+        UploadFailedEvent {
+          photo_id: photo.id.clone(),
+          retry_count: 1,
+          error: error.clone(),
+        },
+      );
+      let _ = app.hillview().show_upload_notification(&photo.id, false, Some(&error));
+      false
+    }
+  }
+}
+
+/// Walks `dirs` recursively, reading EXIF GPS/timestamp metadata out of
+/// every image file found. Returns the photos found and a count of files
+/// that existed but couldn't be read.
+fn scan_directories(dirs: &[PathBuf]) -> (Vec<DevicePhotoMetadata>, i32) {
+  let mut photos = Vec::new();
+  let mut errors = 0;
+  for dir in dirs {
+    walk_dir(dir, &mut photos, &mut errors);
+  }
+  (photos, errors)
+}
+
+fn walk_dir(dir: &Path, photos: &mut Vec<DevicePhotoMetadata>, errors: &mut i32) {
+  let entries = match fs::read_dir(dir) {
+    Ok(entries) => entries,
+    Err(_) => return, // not configured / doesn't exist yet - nothing to scan
+  };
+
+  for entry in entries.flatten() {
+    let path = entry.path();
+    if path.is_dir() {
+      walk_dir(&path, photos, errors);
+      continue;
+    }
+    let is_image = path
+      .extension()
+      .and_then(|ext| ext.to_str())
+      .map(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+      .unwrap_or(false);
+    if !is_image {
+      continue;
+    }
+    match photo_metadata_for(&path) {
+      Ok(photo) => photos.push(photo),
+      Err(e) => {
+        warn!("🢄🖥️ Failed to read metadata for {}: {}", path.display(), e);
+        *errors += 1;
+      }
+    }
+  }
+}
+
+fn photo_metadata_for(path: &Path) -> Result<DevicePhotoMetadata, String> {
+  let file = fs::File::open(path).map_err(|e| e.to_string())?;
+  let file_meta = file.metadata().map_err(|e| e.to_string())?;
+  let file_size = file_meta.len();
+  let created_at = file_meta
+    .modified()
+    .ok()
+    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+    .map(|d| d.as_secs() as i64)
+    .unwrap_or(0);
+
+  let mut reader = std::io::BufReader::new(file);
+  let metadata = read_exif_metadata(&mut reader);
+
+  let id = path.to_string_lossy().to_string();
+  let filename = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+  Ok(DevicePhotoMetadata {
+    id: id.clone(),
+    filename,
+    path: id,
+    metadata,
+    width: 0,
+    height: 0,
+    file_size,
+    created_at,
+    file_hash: None,
+    blurhash: None,
+    thumbnail_256_path: None,
+    thumbnail_1024_path: None,
+  })
+}
+
+/// Reads GPS position/altitude and `DateTimeOriginal` out of a JPEG's EXIF
+/// segment. Missing or unparseable tags fall back to an untagged photo
+/// (`location_source`/`bearing_source` = `"none"`) rather than failing the
+/// whole scan over one file.
+fn read_exif_metadata(reader: &mut std::io::BufReader<fs::File>) -> PhotoMetadata {
+  let mut metadata = PhotoMetadata {
+    latitude: 0.0,
+    longitude: 0.0,
+    altitude: None,
+    bearing: None,
+    timestamp: 0,
+    accuracy: 0.0,
+    location_source: "none".to_string(),
+    bearing_source: "none".to_string(),
+  };
+
+  let Ok(exif) = exif::Reader::new().read_from_container(reader) else {
+    return metadata;
+  };
+
+  if let Some(latitude) = gps_coordinate(&exif, exif::Tag::GPSLatitude, exif::Tag::GPSLatitudeRef, b'S') {
+    metadata.latitude = latitude;
+    metadata.location_source = "exif".to_string();
+  }
+  if let Some(longitude) = gps_coordinate(&exif, exif::Tag::GPSLongitude, exif::Tag::GPSLongitudeRef, b'W') {
+    metadata.longitude = longitude;
+    metadata.location_source = "exif".to_string();
+  }
+  if let Some(field) = exif.get_field(exif::Tag::GPSAltitude, exif::In::PRIMARY) {
+    if let exif::Value::Rational(ref alt) = field.value {
+      if let Some(alt) = alt.first() {
+        metadata.altitude = Some(alt.to_f64());
+      }
+    }
+  }
+  if let Some(field) = exif.get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY) {
+    if let exif::Value::Ascii(ref ascii) = field.value {
+      if let Some(bytes) = ascii.first() {
+        if let Ok(text) = std::str::from_utf8(bytes) {
+          if let Ok(parsed) = chrono::NaiveDateTime::parse_from_str(text, "%Y:%m:%d %H:%M:%S") {
+            metadata.timestamp = parsed.and_utc().timestamp();
+          }
+        }
+      }
+    }
+  }
+
+  metadata
+}
+
+/// Reads a GPS{Latitude,Longitude} tag pair into a signed decimal degree
+/// value, negating it when the paired Ref tag matches `negative_hemisphere`
+/// (`'S'` for latitude, `'W'` for longitude).
+fn gps_coordinate(exif: &exif::Exif, value_tag: exif::Tag, ref_tag: exif::Tag, negative_hemisphere: u8) -> Option<f64> {
+  let dms = match exif.get_field(value_tag, exif::In::PRIMARY)?.value {
+    exif::Value::Rational(ref dms) if dms.len() == 3 => [dms[0].to_f64(), dms[1].to_f64(), dms[2].to_f64()],
+    _ => return None,
+  };
+  let degrees = dms[0] + dms[1] / 60.0 + dms[2] / 3600.0;
+
+  let negative = match &exif.get_field(ref_tag, exif::In::PRIMARY)?.value {
+    exif::Value::Ascii(ascii) => ascii.first().and_then(|r| r.first()) == Some(&negative_hemisphere),
+    _ => false,
+  };
+
+  Some(if negative { -degrees } else { degrees })
 }
@@ -1,6 +1,6 @@
 use tauri::{
   plugin::{Builder, TauriPlugin},
-  Manager, Runtime,
+  Manager, RunEvent, Runtime,
 };
 
 pub use models::*;
@@ -13,10 +13,15 @@ mod mobile;
 mod commands;
 mod error;
 mod models;
+#[cfg(desktop)]
+mod persistence;
 pub mod shared_types;  // Make it public so main app can use it
 
 pub use error::{Error, Result};
-pub use shared_types::{DevicePhotoMetadata, PhotoMetadata, AddPhotoResponse};
+pub use shared_types::{
+  events, AddPhotoResponse, DevicePhotoMetadata, PhotoMetadata, ScanProgressEvent,
+  UploadCompleteEvent, UploadFailedEvent, UploadProgressEvent,
+};
 
 #[cfg(desktop)]
 use desktop::Hillview;
@@ -67,6 +72,12 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
       commands::get_push_registration_status,
       #[cfg(mobile)]
       commands::select_push_distributor,
+      // Notification settings / Tauri permission bridge commands
+      commands::get_notification_settings,
+      commands::set_notification_settings,
+      commands::check_tauri_permissions,
+      commands::request_post_notification_permission,
+      commands::test_show_notification,
 
       ])
     .setup(|app, api| {
@@ -77,5 +88,25 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
       app.manage(hillview);
       Ok(())
     })
+    .on_event(|app, event| match event {
+      // Desktop has its own upload queue to flush; the app is actually
+      // going away, so outstanding retry tasks are aborted rather than
+      // left to race the process exit.
+      #[cfg(desktop)]
+      RunEvent::Exit | RunEvent::ExitRequested { .. } => {
+        app.hillview().cancel_pending_uploads();
+        if let Err(e) = app.hillview().persist_upload_queue() {
+          log::warn!("Failed to persist upload queue on exit: {}", e);
+        }
+      }
+      // Mobile's upload queue lives in the Kotlin/Swift worker, so there's
+      // nothing of ours to persist - just nudge it to pick back up where
+      // it left off.
+      #[cfg(mobile)]
+      RunEvent::Resumed => {
+        let _ = app.hillview().retry_failed_uploads();
+      }
+      _ => {}
+    })
     .build()
 }
@@ -1,28 +1,31 @@
 use serde::de::DeserializeOwned;
 use tauri::{
   plugin::{PluginApi, PluginHandle},
-  AppHandle, Runtime,
+  AppHandle, Emitter, Runtime,
 };
 
 use crate::models::*;
+use crate::shared_types::{events, ScanProgressEvent, UploadCompleteEvent, UploadFailedEvent, UploadProgressEvent};
 
 #[cfg(target_os = "ios")]
 tauri::ios_plugin_binding!(init_plugin_hillview);
 
 // initializes the Kotlin or Swift plugin classes
 pub fn init<R: Runtime, C: DeserializeOwned>(
-  _app: &AppHandle<R>,
+  app: &AppHandle<R>,
   api: PluginApi<R, C>,
 ) -> crate::Result<Hillview<R>> {
   #[cfg(target_os = "android")]
   let handle = api.register_android_plugin("io.github.koo5.hillview.plugin", "ExamplePlugin")?;
   #[cfg(target_os = "ios")]
   let handle = api.register_ios_plugin(init_plugin_hillview)?;
-  Ok(Hillview(handle))
+  Ok(Hillview(handle, app.clone()))
 }
 
-/// Access to the hillview APIs.
-pub struct Hillview<R: Runtime>(PluginHandle<R>);
+/// Access to the hillview APIs. Holds the `AppHandle` (alongside the
+/// `PluginHandle` used to call into the Kotlin/Swift side) so the upload and
+/// scan loops can emit progress events back to the webview.
+pub struct Hillview<R: Runtime>(PluginHandle<R>, AppHandle<R>);
 
 impl<R: Runtime> Hillview<R> {
   pub fn ping(&self, payload: PingRequest) -> crate::Result<PingResponse> {
@@ -145,4 +148,23 @@ impl<R: Runtime> Hillview<R> {
       .run_mobile_plugin("stopPreciseLocationListener", ())
       .map_err(Into::into)
   }
+
+  // Progress events, so the frontend can `listen()` instead of polling
+  // `get_upload_status`/`refresh_photo_scan`.
+
+  pub fn emit_upload_progress(&self, event: UploadProgressEvent) -> crate::Result<()> {
+    self.1.emit(events::UPLOAD_PROGRESS, event).map_err(Into::into)
+  }
+
+  pub fn emit_upload_complete(&self, event: UploadCompleteEvent) -> crate::Result<()> {
+    self.1.emit(events::UPLOAD_COMPLETE, event).map_err(Into::into)
+  }
+
+  pub fn emit_upload_failed(&self, event: UploadFailedEvent) -> crate::Result<()> {
+    self.1.emit(events::UPLOAD_FAILED, event).map_err(Into::into)
+  }
+
+  pub fn emit_scan_progress(&self, event: ScanProgressEvent) -> crate::Result<()> {
+    self.1.emit(events::SCAN_PROGRESS, event).map_err(Into::into)
+  }
 }
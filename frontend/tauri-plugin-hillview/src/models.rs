@@ -40,12 +40,36 @@ pub struct UploadStatusResponse {
   pub auto_upload_prompt_enabled: bool,
   pub pending_uploads: i32,
   pub failed_uploads: i32,
+  /// Slots for uploads on the Rust-side S3 backend, kept separate from the
+  /// native (Android worker) queue above. Neither implementation fills them
+  /// from the S3 backend's own counts: desktop always reports 0, and mobile
+  /// reports whatever the native plugin's response carries (0 when absent).
+  #[serde(default)]
+  pub s3_pending_uploads: i32,
+  #[serde(default)]
+  pub s3_failed_uploads: i32,
   pub error: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct UploadConfig {
   pub server_url: Option<String>,
+  /// S3-compatible bucket settings, stored with the rest of the config. The
+  /// Tauri core's S3 upload path does not read this field; it takes its
+  /// settings from the app's `set_s3_upload_config` command instead.
+  #[serde(default)]
+  pub s3: Option<S3UploadConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct S3UploadConfig {
+  pub bucket: String,
+  pub region: String,
+  /// Custom endpoint for MinIO or other S3-compatible services; `None` uses
+  /// the standard AWS endpoint for `region`.
+  pub endpoint: Option<String>,
+  pub access_key_id: String,
+  pub secret_access_key: String,
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -165,6 +189,16 @@ pub struct NotificationSettingsResponse {
   pub error: Option<String>,
 }
 
+/// A notification to show, mirroring the Web/tauri notification options
+/// model (title, body, optional icon) so desktop and mobile take the same
+/// shape.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NotificationOptions {
+  pub title: String,
+  pub body: String,
+  pub icon: Option<String>,
+}
+
 // Permission-related models
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -0,0 +1,49 @@
+//! Disk persistence for the desktop upload queue, so photos still waiting
+//! to upload (or that failed and are queued for retry) survive an app
+//! restart instead of being lost with the in-memory `DesktopState`.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::shared_types::DevicePhotoMetadata;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedQueue {
+  failed_uploads: Vec<DevicePhotoMetadata>,
+}
+
+fn queue_path<R: Runtime>(app: &AppHandle<R>) -> crate::Result<PathBuf> {
+  let dir = app
+    .path()
+    .app_data_dir()
+    .map_err(|e| crate::Error::from(format!("Failed to resolve app data dir: {}", e).as_str()))?;
+  std::fs::create_dir_all(&dir)
+    .map_err(|e| crate::Error::from(format!("Failed to create app data dir: {}", e).as_str()))?;
+  Ok(dir.join("upload_queue.json"))
+}
+
+/// Loads the persisted queue, falling back to an empty one if it doesn't
+/// exist yet or can't be parsed (e.g. it was written by an older schema).
+pub fn load_queue<R: Runtime>(app: &AppHandle<R>) -> Vec<DevicePhotoMetadata> {
+  let path = match queue_path(app) {
+    Ok(path) => path,
+    Err(_) => return Vec::new(),
+  };
+  let Ok(raw) = std::fs::read_to_string(&path) else {
+    return Vec::new();
+  };
+  serde_json::from_str::<PersistedQueue>(&raw).map(|q| q.failed_uploads).unwrap_or_default()
+}
+
+/// Persists the current set of failed uploads so they can be retried after
+/// the app restarts.
+pub fn save_queue<R: Runtime>(app: &AppHandle<R>, failed_uploads: &[DevicePhotoMetadata]) -> crate::Result<()> {
+  let path = queue_path(app)?;
+  let json = serde_json::to_string(&PersistedQueue {
+    failed_uploads: failed_uploads.to_vec(),
+  })
+  .map_err(|e| crate::Error::from(format!("Failed to serialize upload queue: {}", e).as_str()))?;
+  std::fs::write(&path, json).map_err(|e| crate::Error::from(format!("Failed to write upload queue: {}", e).as_str()))
+}
@@ -26,6 +26,12 @@ pub struct DevicePhotoMetadata {
     pub created_at: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blurhash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_256_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_1024_path: Option<String>,
 }
 
 impl DevicePhotoMetadata {
@@ -47,4 +53,45 @@ pub struct AddPhotoResponse {
     pub photo_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+}
+
+/// Names of the events `Hillview::emit_*` sends over Tauri's event bus, so
+/// the frontend can `listen()` for progress instead of polling
+/// `get_upload_status`/`refresh_photo_scan`.
+pub mod events {
+    pub const UPLOAD_PROGRESS: &str = "hillview://upload-progress";
+    pub const UPLOAD_COMPLETE: &str = "hillview://upload-complete";
+    pub const UPLOAD_FAILED: &str = "hillview://upload-failed";
+    pub const SCAN_PROGRESS: &str = "hillview://scan-progress";
+}
+
+/// Payload of an `events::UPLOAD_PROGRESS` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadProgressEvent {
+    pub photo_id: String,
+    pub bytes_transferred: u64,
+    pub bytes_total: u64,
+    pub retry_count: u32,
+}
+
+/// Payload of an `events::UPLOAD_COMPLETE` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadCompleteEvent {
+    pub photo_id: String,
+    pub bytes_total: u64,
+}
+
+/// Payload of an `events::UPLOAD_FAILED` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadFailedEvent {
+    pub photo_id: String,
+    pub retry_count: u32,
+    pub error: String,
+}
+
+/// Payload of an `events::SCAN_PROGRESS` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanProgressEvent {
+    pub photos_scanned: u32,
+    pub photos_total: Option<u32>,
 }
\ No newline at end of file